@@ -0,0 +1,157 @@
+use std::time::{Duration, Instant};
+
+use serde_derive::Serialize;
+
+use crate::{
+    runner::{DayError, Part},
+    Solver,
+};
+
+/// Timings of a single step, durations are in nanoseconds to keep the json output easy to diff
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub max_ns: u64,
+    /// How many times the step can run in a second based on the median
+    pub per_second: f64,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "at least one sample is needed");
+        samples.sort_unstable();
+        let nanos = |duration: &Duration| duration.as_nanos() as u64;
+        let median = &samples[samples.len() / 2];
+        Self {
+            min_ns: nanos(&samples[0]),
+            median_ns: nanos(median),
+            max_ns: nanos(&samples[samples.len() - 1]),
+            per_second: 1.0 / median.as_secs_f64().max(f64::EPSILON),
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        Duration::from_nanos(self.min_ns)
+    }
+
+    pub fn median(&self) -> Duration {
+        Duration::from_nanos(self.median_ns)
+    }
+
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max_ns)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayBenchmark {
+    pub year: u16,
+    pub day: u8,
+    pub runs: usize,
+    pub input_bytes: usize,
+    /// Parsing throughput based on the median
+    pub parse_bytes_per_second: f64,
+    pub parse: Stats,
+    pub part_1: Option<Stats>,
+    pub part_2: Option<Stats>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkReport {
+    pub runs: usize,
+    pub days: Vec<DayBenchmark>,
+}
+
+fn time<T>(runs: usize, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            let result = f();
+            let elapsed = start.elapsed();
+            drop(result);
+            elapsed
+        })
+        .collect()
+}
+
+/// Runs the parse step and each part `runs` times
+pub fn benchmark(
+    solver: &dyn Solver,
+    input: &str,
+    parts: &[Part],
+    runs: usize,
+) -> Result<DayBenchmark, DayError> {
+    let runs = runs.max(1);
+    // Parse once outside of the timing to report errors and to get the input for the parts
+    let parsed = solver.parse_input(input).map_err(DayError::Parse)?;
+    let parse = Stats::from_samples(time(runs, || solver.parse_input(input)));
+    // Same for the parts, a part that fails isn't timed
+    let part = |part: Part| {
+        let run = || match part {
+            Part::One => parsed.part_1(),
+            Part::Two => parsed.part_2(),
+        };
+        parts
+            .contains(&part)
+            .then(|| {
+                run().map_err(|message| DayError::Part(part, message))?;
+                Ok(Stats::from_samples(time(runs, run)))
+            })
+            .transpose()
+    };
+    let part_1 = part(Part::One)?;
+    let part_2 = part(Part::Two)?;
+
+    Ok(DayBenchmark {
+        year: solver.year(),
+        day: solver.day(),
+        runs,
+        input_bytes: input.len(),
+        parse_bytes_per_second: input.len() as f64 * parse.per_second,
+        parse,
+        part_1,
+        part_2,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use indoc::indoc;
+
+    use super::{benchmark, Stats};
+    use crate::{runner::Part, Day01};
+
+    #[test]
+    pub fn stats() {
+        let stats = Stats::from_samples(
+            [5, 1, 3, 2, 4]
+                .iter()
+                .map(|ms| Duration::from_millis(*ms))
+                .collect(),
+        );
+        assert_eq!(stats.min(), Duration::from_millis(1));
+        assert_eq!(stats.median(), Duration::from_millis(3));
+        assert_eq!(stats.max(), Duration::from_millis(5));
+        assert!((stats.per_second - 1000.0 / 3.0).abs() < 0.001);
+    }
+
+    #[test]
+    pub fn benchmark_day() {
+        let input = indoc! {"
+            199
+            200
+            208
+        "};
+        let result = benchmark(&Day01, input, &[Part::One], 3).unwrap();
+        assert_eq!((result.day, result.runs, result.input_bytes), (1, 3, 12));
+        assert!(result.parse.min_ns <= result.parse.median_ns);
+        assert!(result.parse.median_ns <= result.parse.max_ns);
+        assert!(result.part_1.is_some());
+        assert!(result.part_2.is_none());
+
+        assert!(benchmark(&Day01, "x", &Part::ALL, 3).is_err());
+    }
+}
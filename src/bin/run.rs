@@ -0,0 +1,302 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use advent_of_code_2021::{
+    bench::{benchmark, BenchmarkReport, DayBenchmark, Stats},
+    day18::{self, SnaifishNumber, Snailfish},
+    day19, day21, registry,
+    runner::{
+        input_path, read_input, run, run_parallel, DayAnswers, DayError, DayReport,
+        ExpectedAnswers, Part,
+    },
+    Solver,
+};
+use anyhow::{bail, Context};
+use colored::Colorize;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(name = "run", about = "Runs the advent of code solutions")]
+struct Opt {
+    #[structopt(long, default_value = "2021")]
+    year: u16,
+    /// The day to run
+    #[structopt(long, required_unless = "all")]
+    day: Option<u8>,
+    /// Runs every solved day
+    #[structopt(long, conflicts_with = "day")]
+    all: bool,
+    /// Runs the days concurrently, a failing day doesn't stop the others
+    #[structopt(long, requires = "all", conflicts_with = "bench")]
+    parallel: bool,
+    /// Only run this part, runs both parts by default
+    #[structopt(long)]
+    part: Option<Part>,
+    /// Path to the input, `-` reads from stdin. Defaults to inputs/<year>/<day>.txt
+    #[structopt(long, parse(from_os_str), conflicts_with = "all")]
+    input: Option<PathBuf>,
+    /// Toml file of recorded answers, exits with an error if an answer doesn't match
+    #[structopt(long, parse(from_os_str), conflicts_with = "bench")]
+    expect: Option<PathBuf>,
+    /// Runs the parse step and each part this many times and prints the timings
+    #[structopt(long)]
+    bench: Option<usize>,
+    /// Writes the benchmark results as json to this path
+    #[structopt(long, parse(from_os_str), requires = "bench")]
+    json: Option<PathBuf>,
+    /// Prints every step of the day 18 additions, the day 19 scanner alignment
+    /// or every turn of the day 21 practice game
+    #[structopt(long, requires = "day", conflicts_with = "bench")]
+    trace: bool,
+}
+
+/// Prints the answers and returns false if any of them doesn't match the expected answers
+fn print_answers(answers: &DayAnswers, expected: Option<&ExpectedAnswers>) -> bool {
+    let mismatches = expected
+        .map(|expected| expected.check(answers))
+        .unwrap_or_default();
+
+    println!("{}", format!("Day {}", answers.day).bold());
+    for part in Part::ALL {
+        if let Some(answer) = answers.get(part) {
+            match mismatches.iter().find(|mismatch| mismatch.part == part) {
+                Some(mismatch) => println!(
+                    "  part {}: {} (expected {})",
+                    part,
+                    answer.red(),
+                    mismatch.expected
+                ),
+                None => println!("  part {}: {}", part, answer),
+            }
+        }
+    }
+    mismatches.is_empty()
+}
+
+fn print_error(day: u8, err: &DayError) {
+    println!("{}", format!("Day {}", day).bold());
+    println!("  {}", err.to_string().red());
+}
+
+/// Prints the reports in order and returns false if any day failed or doesn't match
+fn print_reports(reports: &[DayReport], expected: Option<&ExpectedAnswers>) -> bool {
+    let mut ok = true;
+    for report in reports {
+        match &report.result {
+            Ok(answers) => ok &= print_answers(answers, expected),
+            Err(err) => {
+                print_error(report.day, err);
+                ok = false;
+            }
+        }
+        println!("  time: {:.2?}", report.elapsed);
+    }
+    ok
+}
+
+/// Prints each addition with the explode and split steps of its reduction
+fn print_trace(numbers: Vec<SnaifishNumber>) {
+    let mut numbers = numbers.into_iter();
+    let mut result = match numbers.next() {
+        Some(number) => number,
+        None => return,
+    };
+    for number in numbers {
+        for step in result.clone().trace_add(number) {
+            println!("{}", step);
+            result = step.number;
+        }
+        println!();
+    }
+    println!("sum: {}", result);
+    println!("magnitude: {}", result.magnitude());
+}
+
+/// Prints where each scanner is, which way it faces and how much work the alignment did,
+/// including the scanners that couldn't be aligned
+fn print_alignment(scanners: &[day19::Scanner]) {
+    let alignment = day19::align(scanners, &day19::AlignmentOptions::default());
+    for (scanner_id, scanner) in alignment.scanners.iter().enumerate() {
+        match scanner {
+            Some(scanner) => {
+                let rotation = scanner.rotation;
+                println!(
+                    "scanner {} at {} facing x: {} y: {} z: {}",
+                    scanner_id,
+                    scanner.translation,
+                    rotation.x_axis,
+                    rotation.y_axis,
+                    rotation.z_axis
+                );
+            }
+            None => println!("scanner {} not aligned", scanner_id),
+        }
+    }
+    println!();
+    println!("beacons: {}", alignment.beacons.len());
+    println!("{}", alignment.stats);
+}
+
+/// Prints each turn of the practice game with the losing score and the number of rolls
+fn print_game(input: &day21::Data) {
+    let mut game = day21::practice_game(input);
+    for turn in game.by_ref() {
+        println!("{}", turn);
+    }
+    println!();
+    let losing_score = game.scores().into_iter().min().unwrap_or_default();
+    println!("losing score: {}", losing_score);
+    println!("rolls: {}", game.roll_count());
+}
+
+fn print_stats(name: &str, stats: &Stats, throughput: String) {
+    println!(
+        "  {:<7} min {:>10.2?}  median {:>10.2?}  max {:>10.2?}  {}",
+        name,
+        stats.min(),
+        stats.median(),
+        stats.max(),
+        throughput
+    );
+}
+
+fn print_benchmark(result: &DayBenchmark) {
+    println!(
+        "{} ({} bytes, {} runs)",
+        format!("Day {}", result.day).bold(),
+        result.input_bytes,
+        result.runs
+    );
+    let mb_per_second = result.parse_bytes_per_second / 1_000_000.0;
+    print_stats("parse", &result.parse, format!("{:.1} MB/s", mb_per_second));
+    for (name, stats) in [("part 1", &result.part_1), ("part 2", &result.part_2)] {
+        if let Some(stats) = stats {
+            print_stats(name, stats, format!("{:.0} runs/s", stats.per_second));
+        }
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let opt = Opt::from_args();
+    let registry = registry();
+    let parts = match opt.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+    let expected = opt
+        .expect
+        .as_deref()
+        .map(ExpectedAnswers::load)
+        .transpose()?;
+
+    if opt.trace {
+        let day = opt.day.context("--trace requires --day")?;
+        if ![18, 19, 21].contains(&day) {
+            bail!("--trace is only available for days 18, 19 and 21");
+        }
+        let path = opt
+            .input
+            .clone()
+            .unwrap_or_else(|| input_path(opt.year, day));
+        let input = read_input(&path)?;
+        let context = || format!("failed to parse {}", path.display());
+        match day {
+            18 => print_trace(day18::parse(&input).with_context(context)?),
+            19 => print_alignment(&day19::parse_scanners(&input).with_context(context)?),
+            _ => print_game(&day21::parse(&input).with_context(context)?),
+        }
+        return Ok(());
+    }
+
+    let solvers: Vec<&dyn Solver> = if opt.all {
+        registry
+            .iter()
+            .filter(|solver| solver.year() == opt.year)
+            .collect()
+    } else {
+        let day = opt.day.context("either --day or --all is required")?;
+        match registry.get(opt.year, day) {
+            Some(solver) => vec![solver],
+            None => bail!("no solution for {} day {}", opt.year, day),
+        }
+    };
+
+    if opt.parallel {
+        let start = Instant::now();
+        let reports = run_parallel(
+            &solvers,
+            |solver| read_input(&input_path(solver.year(), solver.day())),
+            &parts,
+        );
+        let elapsed = start.elapsed();
+        let ok = print_reports(&reports, expected.as_ref());
+        let total = reports
+            .iter()
+            .map(|report| report.elapsed)
+            .sum::<Duration>();
+        println!(
+            "Ran {} days in {:.2?} ({:.2?} of work)",
+            reports.len(),
+            elapsed,
+            total
+        );
+        if !ok {
+            eprintln!(
+                "{}",
+                "Some days failed or don't match the expected answers".red()
+            );
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut ok = true;
+    let mut benchmarks = Vec::new();
+    for solver in solvers {
+        let path = opt
+            .input
+            .clone()
+            .unwrap_or_else(|| input_path(solver.year(), solver.day()));
+        let input = read_input(&path)?;
+        let context = || format!("failed to parse {}", path.display());
+        if let Some(runs) = opt.bench {
+            let result = match benchmark(solver, &input, &parts, runs) {
+                Ok(result) => result,
+                Err(DayError::Parse(err)) => return Err(err).with_context(context),
+                Err(err) => bail!("day {} {}", solver.day(), err),
+            };
+            print_benchmark(&result);
+            benchmarks.push(result);
+        } else {
+            match run(solver, &input, &parts) {
+                Ok(answers) => ok &= print_answers(&answers, expected.as_ref()),
+                Err(DayError::Parse(err)) => return Err(err).with_context(context),
+                Err(err) => {
+                    print_error(solver.day(), &err);
+                    ok = false;
+                }
+            }
+        }
+    }
+
+    if let (Some(runs), Some(path)) = (opt.bench, &opt.json) {
+        let report = BenchmarkReport {
+            runs,
+            days: benchmarks,
+        };
+        let json = serde_json::to_string_pretty(&report)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    if !ok {
+        eprintln!(
+            "{}",
+            "Some days failed or don't match the expected answers".red()
+        );
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -1,7 +1,11 @@
-type Data = Vec<i32>;
+use crate::error::{parse_number, ParseError};
 
-pub fn parse(input: &str) -> Data {
-    input.lines().map(|l| l.parse().unwrap()).collect()
+const DAY: u8 = 1;
+
+pub type Data = Vec<i32>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    input.lines().map(|l| parse_number(DAY, input, l)).collect()
 }
 
 pub fn part_1(input: &Data) -> usize {
@@ -39,14 +43,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 7);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 5);
     }
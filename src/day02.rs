@@ -1,7 +1,11 @@
 use serde_derive::Deserialize;
 use serde_scan::scan;
 
-type Data = Vec<(Commands, i32)>;
+use crate::error::ParseError;
+
+const DAY: u8 = 2;
+
+pub type Data = Vec<(Commands, i32)>;
 
 #[derive(Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -11,10 +15,13 @@ pub enum Commands {
     Up,
 }
 
-pub fn parse(input: &str) -> Data {
+pub fn parse(input: &str) -> Result<Data, ParseError> {
     input
         .lines()
-        .map(|line| scan!("{} {}" <- line).expect("Failed to parse input"))
+        .map(|line| {
+            scan!("{} {}" <- line)
+                .map_err(|_| ParseError::new(DAY, input, line, "expected `<command> <value>`"))
+        })
         .collect()
 }
 
@@ -63,14 +70,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 150);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 900);
     }
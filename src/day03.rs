@@ -1,7 +1,31 @@
-type Data = Vec<String>;
+use crate::error::{parse_digits, ParseError};
 
-pub fn parse(input: &str) -> Data {
-    input.lines().map(|l| l.to_string()).collect()
+const DAY: u8 = 3;
+
+pub type Data = Vec<String>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let width = input.lines().next().map_or(0, str::len);
+    // The product of two numbers of up to 32 bits fits in the answer
+    if width == 0 || width > 32 {
+        let line = input.lines().next().unwrap_or(input);
+        return Err(ParseError::new(DAY, input, line, "expected 1 to 32 bits"));
+    }
+    input
+        .lines()
+        .map(|l| {
+            parse_digits(DAY, input, l, 2)?;
+            match l.len() == width {
+                true => Ok(l.to_string()),
+                false => Err(ParseError::new(
+                    DAY,
+                    input,
+                    l,
+                    format!("expected {} bits like the first line", width),
+                )),
+            }
+        })
+        .collect()
 }
 
 fn most_common_bit(input: &Data, i: usize) -> usize {
@@ -41,12 +65,16 @@ fn find(input: &mut Data, a: u8, b: u8) -> usize {
     for i in 0..input[0].len() {
         let most_common_bit = most_common_bit(input, i);
         let bit = if most_common_bit == 0 { a } else { b };
-        input.retain(|x| x.as_bytes().get(i) == Some(&bit));
+        // When every number has the same bit the least common one would remove them all
+        if input.iter().any(|x| x.as_bytes().get(i) == Some(&bit)) {
+            input.retain(|x| x.as_bytes().get(i) == Some(&bit));
+        }
         if input.len() == 1 {
-            return usize::from_str_radix(&input[0], 2).unwrap();
+            break;
         }
     }
-    unreachable!("Failed to find a value")
+    // The remaining numbers can only be duplicates
+    usize::from_str_radix(&input[0], 2).unwrap()
 }
 
 #[cfg(test)]
@@ -68,16 +96,31 @@ mod tests {
         01010
     "};
 
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("").unwrap_err();
+        assert_eq!(err.reason, "expected 1 to 32 bits");
+        let err = super::parse("101\n11").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "11"));
+        assert!(super::parse(&"1".repeat(33)).is_err());
+
+        // Inputs the puzzle doesn't cover still have an answer
+        let input = super::parse("1").unwrap();
+        assert_eq!((super::part_1(&input), super::part_2(&input)), (0, 1));
+        let input = super::parse("10\n10").unwrap();
+        assert_eq!(super::part_2(&input), 4);
+    }
+
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 198);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 230);
     }
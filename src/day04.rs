@@ -1,6 +1,10 @@
 use colored::Colorize;
 
-type Data = (Vec<usize>, Vec<Board>);
+use crate::error::{parse_number, ParseError};
+
+const DAY: u8 = 4;
+
+pub type Data = (Vec<usize>, Vec<Board>);
 
 #[derive(Clone)]
 pub struct Board {
@@ -26,19 +30,17 @@ impl std::fmt::Debug for Board {
 }
 
 impl Board {
-    fn new(str: &str) -> Self {
+    fn new(input: &str, str: &str) -> Result<Self, ParseError> {
         let data = str
-            .split('\n')
-            .flat_map(|row| {
-                row.split_whitespace()
-                    .map(|x| x.parse().unwrap())
-                    .map(|x| (x, false))
-            })
-            .collect::<Vec<_>>();
-        Self {
-            data: data.try_into().unwrap(),
+            .split_whitespace()
+            .map(|x| parse_number(DAY, input, x).map(|x| (x, false)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            data: data
+                .try_into()
+                .map_err(|_| ParseError::new(DAY, input, str.trim_end(), "expected a 5x5 board"))?,
             is_bingo: false,
-        }
+        })
     }
 
     fn get(&self, x: usize, y: usize) -> (usize, bool) {
@@ -86,18 +88,27 @@ impl Board {
     }
 }
 
-pub fn parse(input: &str) -> Data {
+pub fn parse(input: &str) -> Result<Data, ParseError> {
     let mut lines = input.split("\n\n");
     let numbers = lines
         .next()
-        .map(|l| {
-            l.split(',')
-                .map(|x| x.parse().unwrap())
-                .collect::<Vec<usize>>()
-        })
-        .unwrap();
-    let boards = lines.map(Board::new).collect::<Vec<_>>();
-    (numbers, boards)
+        .unwrap_or_default()
+        .trim_end()
+        .split(',')
+        .map(|x| parse_number(DAY, input, x))
+        .collect::<Result<Vec<usize>, _>>()?;
+    let boards = lines
+        .map(|board| Board::new(input, board))
+        .collect::<Result<Vec<_>, _>>()?;
+    if boards.is_empty() {
+        return Err(ParseError::new(
+            DAY,
+            input,
+            input,
+            "expected at least one board",
+        ));
+    }
+    Ok((numbers, boards))
 }
 
 pub fn part_1(input: &Data) -> usize {
@@ -163,16 +174,30 @@ mod tests {
         2  0 12  3  7
     "};
 
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("1,2\n\n1 2 3\n4 x 6").unwrap_err();
+        assert_eq!((err.line, err.column), (4, 3));
+        assert_eq!(err.text, "x");
+
+        let err = super::parse("1,2\n\n1 2 3\n4 5 6").unwrap_err();
+        assert_eq!(err.reason, "expected a 5x5 board");
+        assert_eq!(err.line, 3);
+
+        let err = super::parse("1,2\n").unwrap_err();
+        assert_eq!(err.reason, "expected at least one board");
+    }
+
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 4512);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 1924);
     }
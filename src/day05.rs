@@ -1,12 +1,19 @@
 use hashbrown::HashMap;
 use serde_scan::scan;
 
-type Data = Vec<(usize, usize, usize, usize)>;
+use crate::error::ParseError;
 
-pub fn parse(input: &str) -> Data {
+const DAY: u8 = 5;
+
+pub type Data = Vec<(usize, usize, usize, usize)>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
     input
         .lines()
-        .map(|line| scan!("{},{} -> {},{}" <- line).expect("Failed to parse input"))
+        .map(|line| {
+            scan!("{},{} -> {},{}" <- line)
+                .map_err(|_| ParseError::new(DAY, input, line, "expected `x1,y1 -> x2,y2`"))
+        })
         .collect()
 }
 
@@ -75,7 +82,7 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         println!("{:?}", input);
 
         let result = super::part_1(&input);
@@ -84,7 +91,7 @@ mod tests {
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 12);
     }
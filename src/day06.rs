@@ -1,12 +1,16 @@
-type Data = Vec<u8>;
+use crate::error::{parse_number, ParseError};
 
-pub fn parse(input: &str) -> Data {
+const DAY: u8 = 6;
+
+pub type Data = Vec<u8>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
     input
         .lines()
         .next()
-        .unwrap()
+        .ok_or_else(|| ParseError::new(DAY, input, input, "empty input"))?
         .split(',')
-        .map(|l| l.parse().unwrap())
+        .map(|l| parse_number(DAY, input, l))
         .collect()
 }
 
@@ -42,14 +46,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 5934);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 26984457539);
     }
@@ -1,13 +1,17 @@
+use crate::error::{parse_number, ParseError};
+
+const DAY: u8 = 7;
+
 /// See this paper for more detail on the median and mean solution
 /// https://www.reddit.com/r/adventofcode/comments/rawxad/2021_day_7_part_2_i_wrote_a_paper_on_todays/
 
-type Data = Vec<isize>;
+pub type Data = Vec<isize>;
 
-pub fn parse(input: &str) -> Data {
+pub fn parse(input: &str) -> Result<Data, ParseError> {
     input
         .trim_end()
         .split(',')
-        .map(|l| l.parse().unwrap())
+        .map(|l| parse_number(DAY, input, l))
         .collect()
 }
 
@@ -49,14 +53,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 37);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 168);
     }
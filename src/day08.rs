@@ -1,12 +1,20 @@
 use itertools::Itertools;
 
-type Data = Vec<(Vec<Vec<char>>, Vec<Vec<char>>)>;
+use crate::error::ParseError;
 
-pub fn parse(input: &str) -> Data {
+const DAY: u8 = 8;
+
+pub type Data = Vec<(Vec<Vec<char>>, Vec<Vec<char>>)>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
     input
         .lines()
-        .filter_map(|line| line.split_once('|'))
-        .map(|(l, r)| {
+        .map(|line| {
+            line.split_once('|').ok_or_else(|| {
+                ParseError::new(DAY, input, line, "expected `<patterns> | <outputs>`")
+            })
+        })
+        .map_ok(|(l, r)| {
             (
                 l.trim()
                     .split(' ')
@@ -138,22 +146,22 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS_SMALL);
+        let input = super::parse(INPUTS_SMALL).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 0);
 
-        let input = super::parse(INPUTS_LONG);
+        let input = super::parse(INPUTS_LONG).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 26);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS_SMALL);
+        let input = super::parse(INPUTS_SMALL).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 5353);
 
-        let input = super::parse(INPUTS_LONG);
+        let input = super::parse(INPUTS_LONG).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 61229);
     }
@@ -1,7 +1,30 @@
-type Data = Vec<String>;
+use crate::error::ParseError;
 
-pub fn parse(input: &str) -> Data {
-    input.lines().map(|l| l.to_string()).collect()
+const DAY: u8 = 10;
+
+pub type Data = Vec<String>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let lines = input
+        .lines()
+        .map(
+            |l| match l.char_indices().find(|(_, c)| !"()[]{}<>".contains(*c)) {
+                Some((i, c)) => Err(ParseError::new(
+                    DAY,
+                    input,
+                    &l[i..i + c.len_utf8()],
+                    "expected a bracket",
+                )),
+                None => Ok(l.to_string()),
+            },
+        )
+        .collect::<Result<Data, _>>()?;
+    // Part 2 takes the middle score of these lines
+    if !lines.iter().any(|line| complete_line(line).is_ok()) {
+        let reason = "expected at least one line that isn't corrupted";
+        return Err(ParseError::new(DAY, input, input, reason));
+    }
+    Ok(lines)
 }
 
 fn matching(c: char) -> char {
@@ -87,16 +110,27 @@ mod tests {
         <{([{{}}[<[[[<>{}]]]>[]]
     "};
 
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("()\n(é)").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.text, "é");
+
+        let reason = "expected at least one line that isn't corrupted";
+        assert_eq!(super::parse("").unwrap_err().reason, reason);
+        assert_eq!(super::parse("(]").unwrap_err().reason, reason);
+    }
+
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 26397);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 288957);
     }
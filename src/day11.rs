@@ -1,32 +1,27 @@
 use colored::Colorize;
 
-type Data = Vec<Vec<i32>>;
-
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .flat_map(|c| c.to_digit(10))
-                .map(|x| x as i32)
-                .collect()
-        })
-        .collect()
-}
+use crate::{
+    error::ParseError,
+    grid::{Grid, NEIGHBOURS_8},
+};
+
+const DAY: u8 = 11;
 
-const NEIGHBOURS: [(isize, isize); 8] = [
-    (0, 1),
-    (0, -1),
-    (1, 0),
-    (1, 1),
-    (1, -1),
-    (-1, 0),
-    (-1, 1),
-    (-1, -1),
-];
+pub type Data = Grid<i32>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let data = Grid::parse_digits(DAY, input, input)?;
+    if let Some(line) = input.lines().find(|l| l.len() != 10) {
+        return Err(ParseError::new(DAY, input, line, "expected 10 octopuses"));
+    }
+    if data.height() != 10 {
+        return Err(ParseError::new(DAY, input, input, "expected 10 rows"));
+    }
+    Ok(data.map(|x| *x as i32))
+}
 
 fn _print_data(data: &Data) {
-    for row in data {
+    for row in data.rows() {
         for val in row {
             if *val == 0 {
                 print!("{}", val.to_string().yellow());
@@ -40,31 +35,26 @@ fn _print_data(data: &Data) {
 }
 
 fn step(data: &mut Data) -> usize {
-    for value in data.iter_mut().flatten() {
+    for value in data.values_mut() {
         *value += 1;
     }
 
     let mut flashes = 0;
     loop {
         let mut keep_running = false;
-        for y in 0..10 {
-            for x in 0..10 {
-                if data[y][x] <= 9 {
-                    continue;
-                }
+        for pos in data.positions() {
+            if data[pos] <= 9 {
+                continue;
+            }
+
+            flashes += 1;
+            keep_running = true;
+            data[pos] = 0;
 
-                flashes += 1;
-                keep_running = true;
-                data[y][x] = 0;
-
-                for (n_x, n_y) in NEIGHBOURS {
-                    if let Some(val) = data
-                        .get_mut((y as isize + n_y) as usize)
-                        .and_then(|row| row.get_mut((x as isize + n_x) as usize))
-                    {
-                        if *val <= 9 && *val != 0 {
-                            *val += 1;
-                        }
+            for (n_x, n_y) in NEIGHBOURS_8 {
+                if let Some(val) = data.get_mut((pos.0 + n_x, pos.1 + n_y)) {
+                    if *val <= 9 && *val != 0 {
+                        *val += 1;
                     }
                 }
             }
@@ -115,7 +105,7 @@ mod tests {
 
     #[test]
     pub fn step_test() {
-        let mut input = super::parse(INPUTS);
+        let mut input = super::parse(INPUTS).unwrap();
         let mut flashes = 0;
         for _ in 0..10 {
             flashes += step(&mut input);
@@ -125,14 +115,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 1656);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 195);
     }
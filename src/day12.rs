@@ -1,19 +1,44 @@
 use hashbrown::HashMap;
 
+use crate::error::ParseError;
+
+const DAY: u8 = 12;
+
 type Cave = String;
-type Graph = HashMap<Cave, Vec<Cave>>;
+pub type Graph = HashMap<Cave, Vec<Cave>>;
 
-pub fn parse(input: &str) -> Graph {
+pub fn parse(input: &str) -> Result<Graph, ParseError> {
     let mut map = HashMap::new();
     for line in input.lines() {
-        let (l, r) = line.split_once('-').expect("");
+        let (l, r) = line
+            .split_once('-')
+            .ok_or_else(|| ParseError::new(DAY, input, line, "expected `<cave>-<cave>`"))?;
         let l = l.to_string();
         let r = r.to_string();
         map.entry(l.clone()).or_insert(vec![]).push(r.clone());
         map.entry(r.clone()).or_insert(vec![]).push(l.clone());
+        // The paths could go back and forth between them forever
+        if is_big(&l) && is_big(&r) {
+            return Err(ParseError::new(
+                DAY,
+                input,
+                line,
+                "big caves can't be connected",
+            ));
+        }
+    }
+
+    for cave in ["start", "end"] {
+        if !map.contains_key(cave) {
+            let reason = format!("expected a `{}` cave", cave);
+            return Err(ParseError::new(DAY, input, input, reason));
+        }
     }
+    Ok(map)
+}
 
-    map
+fn is_big(cave: &str) -> bool {
+    cave.chars().all(|c| c.is_uppercase())
 }
 
 fn find_paths(graph: &Graph, path: &mut Vec<Cave>, cave: &str, mut repeat_found: bool) -> usize {
@@ -94,32 +119,42 @@ mod tests {
         start-RW
     "};
 
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("start-a\na-b").unwrap_err();
+        assert_eq!(err.reason, "expected a `end` cave");
+        let err = super::parse("a-end").unwrap_err();
+        assert_eq!(err.reason, "expected a `start` cave");
+        let err = super::parse("start-A\nA-B\nB-end").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "A-B"));
+    }
+
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 10);
 
-        let input = super::parse(INPUTS_LARGER);
+        let input = super::parse(INPUTS_LARGER).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 19);
 
-        let input = super::parse(INPUTS_EVEN_LARGER);
+        let input = super::parse(INPUTS_EVEN_LARGER).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 226);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 36);
 
-        let input = super::parse(INPUTS_LARGER);
+        let input = super::parse(INPUTS_LARGER).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 103);
 
-        let input = super::parse(INPUTS_EVEN_LARGER);
+        let input = super::parse(INPUTS_EVEN_LARGER).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 3509);
     }
@@ -1,7 +1,11 @@
 use hashbrown::HashSet;
 use serde_scan::scan;
 
-type Data = (HashSet<(usize, usize)>, Vec<Fold>);
+use crate::error::{parse_number, ParseError};
+
+const DAY: u8 = 13;
+
+pub type Data = (HashSet<(usize, usize)>, Vec<Fold>);
 
 #[derive(Clone, Copy)]
 pub enum Fold {
@@ -9,24 +13,44 @@ pub enum Fold {
     X(usize),
 }
 
-pub fn parse(input: &str) -> Data {
-    let (dots, folds) = input.split_once("\n\n").unwrap();
-
-    (
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let (dots, folds) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(
+            DAY,
+            input,
+            input,
+            "expected the dots and folds separated by an empty line",
+        )
+    })?;
+
+    Ok((
         dots.lines()
-            .flat_map(|l| l.split_once(","))
-            .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
-            .collect(),
+            .map(|l| {
+                let (x, y) = l
+                    .split_once(',')
+                    .ok_or_else(|| ParseError::new(DAY, input, l, "expected `x,y`"))?;
+                Ok((parse_number(DAY, input, x)?, parse_number(DAY, input, y)?))
+            })
+            .collect::<Result<_, _>>()?,
         folds
             .lines()
-            .flat_map(|l| scan!("fold along {}={}" <- l))
-            .map(|(direction, value)| match direction {
-                'y' => Fold::Y(value),
-                'x' => Fold::X(value),
-                _ => unreachable!(),
+            .map(|l| {
+                let (direction, value) = scan!("fold along {}={}" <- l).map_err(|_| {
+                    ParseError::new(DAY, input, l, "expected `fold along <axis>=<value>`")
+                })?;
+                match direction {
+                    'y' => Ok(Fold::Y(value)),
+                    'x' => Ok(Fold::X(value)),
+                    _ => Err(ParseError::new(
+                        DAY,
+                        input,
+                        l,
+                        "expected a fold along x or y",
+                    )),
+                }
             })
-            .collect(),
-    )
+            .collect::<Result<_, _>>()?,
+    ))
 }
 
 fn fold(dots: &mut HashSet<(usize, usize)>, fold: Fold) {
@@ -112,14 +136,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 17);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 16);
     }
@@ -1,25 +1,44 @@
 use hashbrown::HashMap;
 use serde_scan::scan;
 
+use crate::error::ParseError;
+
+const DAY: u8 = 14;
+
 type Pair = (char, char);
 
-type Data = (Vec<char>, HashMap<Pair, (Pair, Pair)>);
+pub type Data = (Vec<char>, HashMap<Pair, (Pair, Pair)>);
 
-pub fn parse(input: &str) -> Data {
-    let (template, insertions) = input.split_once("\n\n").unwrap();
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let (template, insertions) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(
+            DAY,
+            input,
+            input,
+            "expected the template and rules separated by an empty line",
+        )
+    })?;
 
-    (
+    Ok((
         template.chars().collect(),
         insertions
             .lines()
-            .flat_map(|l| scan!("{} -> {}" <- l))
-            .map(|(pair, output): (String, char)| {
-                let mut chars = pair.chars();
-                let pair = (chars.next().unwrap(), chars.next().unwrap());
-                (pair, ((pair.0, output), (output, pair.1)))
+            .map(|l| {
+                let (pair, output): (String, char) = scan!("{} -> {}" <- l).map_err(|_| {
+                    ParseError::new(DAY, input, l, "expected `<pair> -> <element>`")
+                })?;
+                match pair.chars().collect::<Vec<_>>()[..] {
+                    [a, b] => Ok(((a, b), ((a, output), (output, b)))),
+                    _ => Err(ParseError::new(
+                        DAY,
+                        input,
+                        l,
+                        "expected a pair of elements",
+                    )),
+                }
             })
-            .collect(),
-    )
+            .collect::<Result<_, _>>()?,
+    ))
 }
 
 fn solve(input: &Data, iterations: usize) -> usize {
@@ -87,14 +106,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 1588);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 2188189693529);
     }
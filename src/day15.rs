@@ -1,86 +1,135 @@
-use std::collections::BinaryHeap;
-
-type Data = Vec<Vec<i32>>;
-
-type Point = (usize, usize);
-
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|l| {
-            l.chars()
-                .flat_map(|c| c.to_digit(10))
-                .map(|x| x as i32)
-                .collect()
-        })
-        .collect()
+use colored::Colorize;
+use hashbrown::HashSet;
+
+use crate::{
+    error::ParseError,
+    grid::{Grid, Pos},
+    pathfinding::{grid_dijkstra, Path},
+};
+
+const DAY: u8 = 15;
+
+pub type Data = Grid<i32>;
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    Grid::parse_digits(DAY, input, input).map(|grid| grid.map(|x| *x as i32))
 }
 
-fn shortest_path(data: &Data, start: Point, goal: Point) -> i32 {
-    let mut dist = vec![vec![i32::MAX; data[0].len()]; data.len()];
+/// Anything that can give the risk of a position, lets the tiled map be computed on demand
+///
+/// The risks are between 0 and 9, no path is found through a map with other risks
+pub trait RiskMap {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn risk(&self, pos: Pos) -> Option<i32>;
+}
 
-    let mut queue = BinaryHeap::new();
-    queue.push((0, start.0, start.1));
+impl RiskMap for Data {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
 
-    // WARN
-    // The cost needs to be the first element of the tuple
-    // Rust will check the elements in order and compare them until one is not equal to the other
-    while let Some((cost, x, y)) = queue.pop() {
-        if (x, y) == goal {
-            return -cost;
-        }
-        if -cost > dist[y][x] {
-            continue;
-        }
-        for (n_x, n_y) in [
-            (x.wrapping_sub(1), y),
-            (x + 1, y),
-            (x, y.wrapping_sub(1)),
-            (x, y + 1),
-        ] {
-            if let Some(n_cost) = data.get(n_y).and_then(|row| row.get(n_x)) {
-                let n_cost = -cost + n_cost;
-                if n_cost < dist[n_y][n_x] {
-                    queue.push((-n_cost, n_x, n_y));
-                    dist[n_y][n_x] = n_cost;
-                }
-            }
-        }
+    fn height(&self) -> usize {
+        Grid::height(self)
     }
 
-    dist[goal.1][goal.0]
+    fn risk(&self, pos: Pos) -> Option<i32> {
+        self.get(pos).copied()
+    }
+}
+
+/// The map repeated `factor` times in both directions,
+/// each repetition to the right or down increases the risk by 1 and wraps back to 1 after 9
+pub struct TiledMap<'a> {
+    tile: &'a Data,
+    factor: usize,
 }
 
-fn enlarge_map(input: &Data) -> Data {
-    let height = input.len();
-    let width = input[0].len();
-    let mut larger_map = vec![vec![0; width * 5]; height * 5];
-    for y in 0..larger_map.len() {
-        for x in 0..larger_map[0].len() {
-            larger_map[y][x] = {
-                let cost = input[y % height][x % width] + (x / width) as i32 + (y / height) as i32;
-                if cost <= 9 {
-                    cost
-                } else {
-                    cost - 9
-                }
-            };
+impl<'a> TiledMap<'a> {
+    pub fn new(tile: &'a Data, factor: usize) -> Self {
+        Self { tile, factor }
+    }
+}
+
+impl RiskMap for TiledMap<'_> {
+    fn width(&self) -> usize {
+        self.tile.width() * self.factor
+    }
+
+    fn height(&self) -> usize {
+        self.tile.height() * self.factor
+    }
+
+    fn risk(&self, (x, y): Pos) -> Option<i32> {
+        if x < 0 || y < 0 || x as usize >= self.width() || y as usize >= self.height() {
+            return None;
         }
+        let width = self.tile.width() as isize;
+        let height = self.tile.height() as isize;
+        let value = self.tile[(x % width, y % height)];
+        let (tile_x, tile_y) = ((x / width) as i32, (y / height) as i32);
+        Some((value + tile_x + tile_y - 1) % 9 + 1)
+    }
+}
+
+fn shortest_path(map: &impl RiskMap, start: Pos, goal: Pos) -> Option<Path<Pos, i32>> {
+    let size = (map.width(), map.height());
+    // Negative risks become too big to be accepted
+    let risk = |pos| {
+        map.risk(pos)
+            .map(|risk| u32::try_from(risk).unwrap_or(u32::MAX))
+    };
+    let path = grid_dijkstra(size, start, goal, 9, risk)?;
+    Some(Path {
+        cost: path.cost as i32,
+        nodes: path.nodes,
+    })
+}
+
+pub fn enlarge_map(input: &Data) -> Data {
+    let tiled = TiledMap::new(input, 5);
+    let mut larger_map = Grid::new(tiled.width(), tiled.height(), 0);
+    for pos in larger_map.positions() {
+        larger_map[pos] = tiled.risk(pos).expect("position is inside the tiled map");
     }
     larger_map
 }
 
+fn bottom_right(map: &impl RiskMap) -> Pos {
+    (map.width() as isize - 1, map.height() as isize - 1)
+}
+
+/// The cells of the lowest risk route from the top left to the bottom right corner
+///
+/// The start isn't entered so its risk isn't part of the cost
+pub fn lowest_risk_path(map: &impl RiskMap) -> Option<Path<Pos, i32>> {
+    shortest_path(map, (0, 0), bottom_right(map))
+}
+
+/// Renders the map with the cells of the path highlighted
+pub fn render_path(map: &impl RiskMap, path: &[Pos]) -> String {
+    let path = path.iter().collect::<HashSet<_>>();
+    let mut output = String::new();
+    for y in 0..map.height() as isize {
+        for x in 0..map.width() as isize {
+            let risk = map.risk((x, y)).unwrap_or_default().to_string();
+            if path.contains(&(x, y)) {
+                output += &risk.yellow().bold().to_string();
+            } else {
+                output += &risk.dimmed().to_string();
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
 pub fn part_1(input: &Data) -> i32 {
-    shortest_path(input, (0, 0), (input.len() - 1, input[0].len() - 1))
+    lowest_risk_path(input).map_or(i32::MAX, |path| path.cost)
 }
 
 pub fn part_2(input: &Data) -> i32 {
-    let large_map = enlarge_map(input);
-    shortest_path(
-        &large_map,
-        (0, 0),
-        (large_map.len() - 1, large_map[0].len() - 1),
-    )
+    lowest_risk_path(&TiledMap::new(input, 5)).map_or(i32::MAX, |path| path.cost)
 }
 
 #[cfg(test)]
@@ -155,37 +204,107 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 40);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 315);
     }
 
+    #[test]
+    pub fn path() {
+        let input = super::parse(INPUTS).unwrap();
+        let path = super::lowest_risk_path(&input).unwrap();
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(9, 9)));
+        let risk = path.nodes[1..].iter().map(|pos| input[*pos]).sum::<i32>();
+        assert_eq!(risk, 40);
+
+        colored::control::set_override(false);
+        let rendered = super::render_path(&input, &path.nodes);
+        assert_eq!(rendered, INPUTS);
+
+        let large_map = super::enlarge_map(&input);
+        let path = super::lowest_risk_path(&large_map).unwrap();
+        assert_eq!(path.cost, 315);
+        assert_eq!(path.nodes.last(), Some(&(49, 49)));
+    }
+
+    #[test]
+    pub fn tiled_map() {
+        use super::{RiskMap, TiledMap};
+
+        let input = super::parse("8").unwrap();
+        let tiled = TiledMap::new(&input, 10);
+        assert_eq!((tiled.width(), tiled.height()), (10, 10));
+        assert_eq!(tiled.risk((1, 0)), Some(9));
+        assert_eq!(tiled.risk((2, 0)), Some(1));
+        assert_eq!(tiled.risk((9, 9)), Some(8));
+        assert_eq!(tiled.risk((10, 0)), None);
+        assert_eq!(tiled.risk((0, -1)), None);
+
+        let input = super::parse(INPUTS).unwrap();
+        let large_map = super::parse(LARGE_MAP).unwrap();
+        let tiled = TiledMap::new(&input, 5);
+        assert!(large_map
+            .iter()
+            .all(|(pos, risk)| tiled.risk(pos) == Some(*risk)));
+        let path = super::lowest_risk_path(&TiledMap::new(&input, 1)).unwrap();
+        assert_eq!(path.cost, 40);
+
+        // The risks of a map are always checked
+        let input = super::parse("01").unwrap().map(|risk| risk * 10);
+        assert_eq!(super::lowest_risk_path(&input), None);
+        let input = super::parse("10").unwrap().map(|risk| risk - 1);
+        assert_eq!(super::lowest_risk_path(&input), None);
+    }
+
+    #[test]
+    pub fn large_factor() {
+        use super::{lowest_risk_path, RiskMap, TiledMap};
+
+        let input = super::parse(INPUTS).unwrap();
+        let tiled = TiledMap::new(&input, 100);
+        let path = lowest_risk_path(&tiled).unwrap();
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(999, 999)));
+        for step in path.nodes.windows(2) {
+            let (a, b) = (step[0], step[1]);
+            assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs(), 1);
+        }
+        let risk = path.nodes[1..]
+            .iter()
+            .map(|pos| tiled.risk(*pos).unwrap())
+            .sum::<i32>();
+        assert_eq!(risk, path.cost);
+    }
+
     #[test]
     pub fn large_map() {
-        let input = super::parse("8");
+        let input = super::parse("8").unwrap();
         let result = super::enlarge_map(&input);
         let expected = super::parse(indoc! {"
-            8 9 1 2 3
-            9 1 2 3 4
-            1 2 3 4 5
-            2 3 4 5 6
-            3 4 5 6 7
-        "});
+            89123
+            91234
+            12345
+            23456
+            34567
+        "})
+        .unwrap();
         assert_eq!(result, expected);
 
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::enlarge_map(&input);
-        let expected = super::parse(LARGE_MAP);
+        let expected = super::parse(LARGE_MAP).unwrap();
 
         for y in 0..50 {
-            assert_eq!(result[y], expected[y]);
+            assert_eq!(result.row(y), expected.row(y));
         }
     }
 }
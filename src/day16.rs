@@ -1,50 +1,378 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Read},
+};
+
 use bitvec::{order::Msb0, prelude::BitVec};
+use num_bigint::BigUint;
+use serde::{Serialize, Serializer};
+use serde_derive::Serialize;
 
-#[derive(Debug, PartialEq)]
-enum Operator {
-    Sum,
-    Product,
-    Min,
-    Max,
-    GreaterThan,
-    LessThan,
-    EqualtTo,
-}
+use crate::error::ParseError;
+
+const DAY: u8 = 16;
+
+/// The type id of an operator packet, an [`OperatorRegistry`] gives its name and how it's evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Operator(pub usize);
 
 impl Operator {
-    fn from(val: usize) -> Self {
-        match val {
-            0 => Operator::Sum,
-            1 => Operator::Product,
-            2 => Operator::Min,
-            3 => Operator::Max,
-            5 => Operator::GreaterThan,
-            6 => Operator::LessThan,
-            7 => Operator::EqualtTo,
-            _ => unreachable!("invalid operator: {}", val),
+    pub const SUM: Operator = Operator(0);
+    pub const PRODUCT: Operator = Operator(1);
+    pub const MIN: Operator = Operator(2);
+    pub const MAX: Operator = Operator(3);
+    pub const GREATER_THAN: Operator = Operator(5);
+    pub const LESS_THAN: Operator = Operator(6);
+    pub const EQUAL_TO: Operator = Operator(7);
+}
+
+const LITERAL_ID: usize = 4;
+
+/// The value of a literal, kept as a `u128` unless it doesn't fit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Small(u128),
+    /// Always bigger than `u128::MAX`
+    Big(BigUint),
+}
+
+impl Value {
+    pub fn to_u128(&self) -> Option<u128> {
+        match self {
+            Value::Small(value) => Some(*value),
+            Value::Big(_) => None,
+        }
+    }
+
+    fn push_nibble(&mut self, nibble: u8) {
+        match self {
+            Value::Small(value) if value.leading_zeros() >= 4 => {
+                *value = *value << 4 | nibble as u128
+            }
+            Value::Small(value) => {
+                *self = Value::Big(BigUint::from(*value) << 4u8 | BigUint::from(nibble))
+            }
+            Value::Big(value) => *value = &*value << 4u8 | BigUint::from(nibble),
+        }
+    }
+
+    /// Number of bits without the leading zeros
+    fn bits(&self) -> u64 {
+        match self {
+            Value::Small(value) => (u128::BITS - value.leading_zeros()) as u64,
+            Value::Big(value) => value.bits(),
+        }
+    }
+
+    /// The nibble at `index`, 0 being the least significant
+    fn nibble(&self, index: u64) -> usize {
+        match self {
+            Value::Small(value) => (value >> (index * 4) & 0xF) as usize,
+            Value::Big(value) => {
+                let shift = index % 8 * 4;
+                value
+                    .iter_u32_digits()
+                    .nth((index / 8) as usize)
+                    .map_or(0, |digit| digit >> shift & 0xF) as usize
+            }
+        }
+    }
+}
+
+impl From<u128> for Value {
+    fn from(value: u128) -> Self {
+        Value::Small(value)
+    }
+}
+
+impl From<BigUint> for Value {
+    fn from(value: BigUint) -> Self {
+        match u128::try_from(&value) {
+            Ok(value) => Value::Small(value),
+            Err(_) => Value::Big(value),
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Small(value) => write!(f, "{}", value),
+            Value::Big(value) => write!(f, "{}", value),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum PacketType {
-    Literal(usize),
+/// Serialized as a number when it fits in a `u64` and as a decimal string otherwise,
+/// most json readers can't represent bigger numbers
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.to_u128().and_then(|value| u64::try_from(value).ok()) {
+            Some(value) => serializer.serialize_u64(value),
+            None => serializer.collect_str(self),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketType {
+    Literal(Value),
     Operator(Operator, Vec<Packet>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Packet {
-    version: usize,
-    packet_type: PacketType,
+    pub version: usize,
+    pub packet_type: PacketType,
 }
 
 impl Packet {
-    fn new(version: usize, packet_type: PacketType) -> Self {
+    pub fn new(version: usize, packet_type: PacketType) -> Self {
         Self {
             version,
             packet_type,
         }
     }
+
+    /// Dumps the whole tree as json with the names of the built-in operators
+    pub fn to_json(&self) -> String {
+        self.to_json_with(&BUILT_IN_OPERATORS)
+    }
+
+    /// Dumps the whole tree as json with the operator names of the registry
+    pub fn to_json_with(&self, registry: &OperatorRegistry) -> String {
+        serde_json::to_string_pretty(&PacketJson::new(self, registry))
+            .expect("packets can always be serialized")
+    }
+
+    /// Prints the packet as an s-expression with the operator names of the registry
+    pub fn display<'a>(&'a self, registry: &'a OperatorRegistry) -> PacketDisplay<'a> {
+        PacketDisplay {
+            packet: self,
+            registry,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PacketJson<'a> {
+    version: usize,
+    packet_type: PacketTypeJson<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum PacketTypeJson<'a> {
+    Literal(&'a Value),
+    Operator {
+        type_id: usize,
+        /// `None` if the type id isn't registered
+        name: Option<&'a str>,
+        packets: Vec<PacketJson<'a>>,
+    },
+}
+
+impl<'a> PacketJson<'a> {
+    fn new(packet: &'a Packet, registry: &'a OperatorRegistry) -> Self {
+        let packet_type = match &packet.packet_type {
+            PacketType::Literal(value) => PacketTypeJson::Literal(value),
+            PacketType::Operator(operator, packets) => PacketTypeJson::Operator {
+                type_id: operator.0,
+                name: registry.name(*operator),
+                packets: packets
+                    .iter()
+                    .map(|packet| PacketJson::new(packet, registry))
+                    .collect(),
+            },
+        };
+        Self {
+            version: packet.version,
+            packet_type,
+        }
+    }
+}
+
+/// Prints the packet with the built-in operator names, see [`PacketDisplay`]
+impl std::fmt::Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.display(&BUILT_IN_OPERATORS), f)
+    }
+}
+
+/// Prints the packet as an s-expression like `(sum (min 1 2) (> 3 4))`,
+/// the alternate form `{:#}` also annotates the versions like `(sum[v3] 1[v0] 2[v5])`.
+/// The operators missing from the registry are printed with their type id like `op5`
+pub struct PacketDisplay<'a> {
+    packet: &'a Packet,
+    registry: &'a OperatorRegistry,
+}
+
+impl std::fmt::Display for PacketDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = |f: &mut std::fmt::Formatter<'_>| {
+            if f.alternate() {
+                write!(f, "[v{}]", self.packet.version)
+            } else {
+                Ok(())
+            }
+        };
+        match &self.packet.packet_type {
+            PacketType::Literal(value) => {
+                write!(f, "{}", value)?;
+                version(f)
+            }
+            PacketType::Operator(operator, packets) => {
+                match self.registry.name(*operator) {
+                    Some(name) => write!(f, "({}", name)?,
+                    None => write!(f, "(op{}", operator.0)?,
+                }
+                version(f)?;
+                for packet in packets {
+                    write!(f, " ")?;
+                    std::fmt::Display::fmt(&packet.display(self.registry), f)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The transmission ended in the middle of a packet
+    Truncated,
+    BadHexDigit(char),
+    /// The sub-packets of a length type 0 operator are longer than its length
+    LengthMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// The bits after the outermost packet aren't all zeros
+    NonZeroPadding,
+    /// Reading the stream failed
+    Io(std::io::ErrorKind),
+}
+
+impl std::fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeErrorKind::Truncated => write!(f, "truncated packet"),
+            DecodeErrorKind::BadHexDigit(c) => write!(f, "bad hex digit `{}`", c),
+            DecodeErrorKind::LengthMismatch { expected, actual } => write!(
+                f,
+                "sub-packets are {} bits long instead of {}",
+                actual, expected
+            ),
+            DecodeErrorKind::NonZeroPadding => write!(f, "non-zero padding"),
+            DecodeErrorKind::Io(kind) => write!(f, "read error ({})", kind),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub kind: DecodeErrorKind,
+    /// Offset in bits from the start of the transmission or of the stream
+    pub offset: usize,
+    /// Index of each sub-packet leading from the outermost packet to the one where the error happened
+    pub path: Vec<usize>,
+}
+
+impl DecodeError {
+    fn new(kind: DecodeErrorKind, offset: usize) -> Self {
+        Self {
+            kind,
+            offset,
+            path: vec![],
+        }
+    }
+
+    fn in_sub_packet(mut self, index: usize) -> Self {
+        self.path.insert(0, index);
+        self
+    }
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at bit {}", self.kind, self.offset)?;
+        if !self.path.is_empty() {
+            let path = self.path.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+            write!(f, " in sub-packet {}", path.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Receives the packets in the order they appear in the transmission
+pub trait PacketVisitor {
+    /// Called before the sub-packets of the operator
+    fn enter_operator(&mut self, version: usize, operator: Operator);
+    fn literal(&mut self, version: usize, value: Value);
+    /// Called after the last sub-packet of the operator
+    fn exit_operator(&mut self, operator: Operator);
+}
+
+/// Rebuilds the packet tree from the visitor calls
+#[derive(Default)]
+struct PacketBuilder {
+    operators: Vec<(usize, Operator, Vec<Packet>)>,
+    packet: Option<Packet>,
+}
+
+impl PacketBuilder {
+    fn push(&mut self, packet: Packet) {
+        match self.operators.last_mut() {
+            Some((_, _, packets)) => packets.push(packet),
+            None => self.packet = Some(packet),
+        }
+    }
+}
+
+impl PacketVisitor for PacketBuilder {
+    fn enter_operator(&mut self, version: usize, operator: Operator) {
+        self.operators.push((version, operator, vec![]));
+    }
+
+    fn literal(&mut self, version: usize, value: Value) {
+        self.push(Packet::new(version, PacketType::Literal(value)));
+    }
+
+    fn exit_operator(&mut self, _operator: Operator) {
+        let (version, operator, packets) = self
+            .operators
+            .pop()
+            .expect("exit_operator is called after enter_operator");
+        self.push(Packet::new(
+            version,
+            PacketType::Operator(operator, packets),
+        ));
+    }
+}
+
+trait BitSource {
+    /// Number of bits read since the start of the stream
+    fn offset(&self) -> usize;
+
+    fn next_bit(&mut self) -> Result<bool, DecodeError>;
+
+    /// Reads `size` bits as a number, a truncated field is reported at its start
+    fn advance(&mut self, size: usize) -> Result<usize, DecodeError> {
+        let start = self.offset();
+        let mut acc = 0;
+        for _ in 0..size {
+            let bit = self.next_bit().map_err(|mut err| {
+                if err.kind == DecodeErrorKind::Truncated {
+                    err.offset = start;
+                }
+                err
+            })?;
+            acc = acc << 1 | bit as usize;
+        }
+        Ok(acc)
+    }
 }
 
 struct BitReader {
@@ -53,155 +381,718 @@ struct BitReader {
 }
 
 impl BitReader {
-    fn from_hex_string(str: &str) -> Self {
-        let bits = BitVec::from_iter((0..str.len()).step_by(2).map(|i| {
-            u8::from_str_radix(&str[i..=i + 1], 16)
-                .unwrap_or_else(|_| panic!("trying to parse {}", &str[i..i + 2]))
-        }));
-        Self {
+    fn from_hex_string(str: &str) -> Result<Self, DecodeError> {
+        let mut bits = BitVec::with_capacity(str.len() * 4);
+        for (i, c) in str.chars().enumerate() {
+            let nibble = c
+                .to_digit(16)
+                .ok_or_else(|| DecodeError::new(DecodeErrorKind::BadHexDigit(c), i * 4))?;
+            bits.extend((0..4).rev().map(|i| nibble >> i & 1 == 1));
+        }
+        Ok(Self {
             data: bits,
             current: 0,
-        }
+        })
     }
+}
 
-    fn advance(&mut self, size: usize) -> usize {
-        let mut acc = 0;
-        let target = self.current + size;
-        while self.current < target {
-            acc = acc << 1 | self.next() as usize;
-        }
-        acc
+impl BitSource for BitReader {
+    fn offset(&self) -> usize {
+        self.current
     }
 
-    fn next(&mut self) -> bool {
-        self.current += 1;
-        self.data[self.current - 1]
+    fn next_bit(&mut self) -> Result<bool, DecodeError> {
+        match self.data.get(self.current) {
+            Some(bit) => {
+                self.current += 1;
+                Ok(*bit)
+            }
+            None => Err(DecodeError::new(DecodeErrorKind::Truncated, self.current)),
+        }
     }
 }
 
-fn parse_packet(reader: &mut BitReader) -> Packet {
-    let version = reader.advance(3);
-    let packet_type = match reader.advance(3) {
-        4 => {
-            let mut acc = 0;
+fn visit_packet(
+    bits: &mut impl BitSource,
+    visitor: &mut impl PacketVisitor,
+) -> Result<(), DecodeError> {
+    let version = bits.advance(3)?;
+    match bits.advance(3)? {
+        LITERAL_ID => {
+            let mut value = Value::Small(0);
             loop {
-                let is_last_packet = reader.next();
-                let val = reader.advance(4);
-                acc = acc << 4 | val;
+                let is_last_packet = bits.next_bit()?;
+                value.push_nibble(bits.advance(4)? as u8);
                 if !is_last_packet {
                     break;
                 }
             }
-            PacketType::Literal(acc)
+            visitor.literal(version, value);
         }
-        operator => {
-            let length_type_id = reader.next();
-            let mut packets = vec![];
-            match length_type_id {
+        id => {
+            // Any other type id is an operator, whether it's known is up to the evaluation
+            let operator = Operator(id);
+            visitor.enter_operator(version, operator);
+            match bits.next_bit()? {
                 false => {
-                    let length = reader.advance(15);
-                    let target = reader.current + length;
-                    while reader.current < target {
-                        packets.push(parse_packet(reader));
+                    let length = bits.advance(15)?;
+                    let start = bits.offset();
+                    let mut index = 0;
+                    while bits.offset() < start + length {
+                        visit_packet(bits, visitor).map_err(|err| err.in_sub_packet(index))?;
+                        index += 1;
+                    }
+                    if bits.offset() > start + length {
+                        let kind = DecodeErrorKind::LengthMismatch {
+                            expected: length,
+                            actual: bits.offset() - start,
+                        };
+                        return Err(DecodeError::new(kind, start));
                     }
                 }
                 true => {
-                    let length = reader.advance(11);
-                    while packets.len() < length {
-                        packets.push(parse_packet(reader));
+                    let length = bits.advance(11)?;
+                    for index in 0..length {
+                        visit_packet(bits, visitor).map_err(|err| err.in_sub_packet(index))?;
                     }
                 }
             };
-            PacketType::Operator(Operator::from(operator), packets)
+            visitor.exit_operator(operator);
         }
-    };
-    Packet::new(version, packet_type)
+    }
+    Ok(())
+}
+
+/// Decodes a hex transmission containing a single packet followed by zeros
+pub fn decode(hex: &str) -> Result<Packet, DecodeError> {
+    let mut reader = BitReader::from_hex_string(hex)?;
+    let mut builder = PacketBuilder::default();
+    visit_packet(&mut reader, &mut builder)?;
+    if let Some(offset) = reader.data[reader.current..].first_one() {
+        let offset = reader.current + offset;
+        return Err(DecodeError::new(DecodeErrorKind::NonZeroPadding, offset));
+    }
+    Ok(builder.packet.expect("a whole packet was visited"))
 }
 
-pub fn parse(input: &str) -> Packet {
-    let input = input.trim_end();
-    parse_packet(&mut BitReader::from_hex_string(input))
+/// How the transmissions of a stream are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Hex digits, the transmissions are separated by whitespace like the puzzle inputs
+    Hex,
+    /// Raw bytes, every transmission is padded to a whole number of bytes
+    Bytes,
 }
 
-pub fn part_1(packet: &Packet) -> usize {
-    match &packet.packet_type {
-        PacketType::Literal(_) => packet.version,
-        PacketType::Operator(_op, packets) => {
-            packet.version + packets.iter().map(part_1).sum::<usize>()
+/// Decodes the transmissions of a stream one at a time, reading through a [`BufReader`].
+///
+/// The memory used per transmission depends on the visitor: [`StreamDecoder::visit_transmission`]
+/// only keeps the enclosing operators on the stack while iterating builds the whole [`Packet`]
+pub struct StreamDecoder<R> {
+    reader: BufReader<R>,
+    encoding: Encoding,
+    /// The bits of the current hex digit or byte that haven't been read yet
+    buffer: u8,
+    remaining: u8,
+    offset: usize,
+    failed: bool,
+}
+
+impl<R: Read> StreamDecoder<R> {
+    pub fn new(reader: R, encoding: Encoding) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            encoding,
+            buffer: 0,
+            remaining: 0,
+            offset: 0,
+            failed: false,
+        }
+    }
+
+    fn peek_byte(&mut self) -> Result<Option<u8>, DecodeError> {
+        match self.reader.fill_buf() {
+            Ok(buf) => Ok(buf.first().copied()),
+            Err(err) => Err(DecodeError::new(
+                DecodeErrorKind::Io(err.kind()),
+                self.offset,
+            )),
+        }
+    }
+
+    /// Loads the next hex digit or byte, returns false at the end of the transmission
+    fn fill(&mut self) -> Result<bool, DecodeError> {
+        let byte = match self.peek_byte()? {
+            Some(byte) => byte,
+            None => return Ok(false),
+        };
+        self.reader.consume(1);
+        match self.encoding {
+            Encoding::Hex if byte.is_ascii_whitespace() => return Ok(false),
+            Encoding::Hex => {
+                let nibble = (byte as char).to_digit(16).ok_or_else(|| {
+                    // only the first byte of other utf-8 characters is known
+                    let c = match byte.is_ascii() {
+                        true => byte as char,
+                        false => char::REPLACEMENT_CHARACTER,
+                    };
+                    DecodeError::new(DecodeErrorKind::BadHexDigit(c), self.offset)
+                })?;
+                self.buffer = nibble as u8;
+                self.remaining = 4;
+            }
+            Encoding::Bytes => {
+                self.buffer = byte;
+                self.remaining = 8;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Skips the whitespace before the next transmission, returns false at the end of the stream
+    fn start_transmission(&mut self) -> Result<bool, DecodeError> {
+        loop {
+            match self.peek_byte()? {
+                Some(byte) if self.encoding == Encoding::Hex && byte.is_ascii_whitespace() => {
+                    self.reader.consume(1)
+                }
+                byte => return Ok(byte.is_some()),
+            }
+        }
+    }
+
+    /// Checks that the rest of the transmission is zeros
+    fn finish_transmission(&mut self) -> Result<(), DecodeError> {
+        loop {
+            while self.remaining > 0 {
+                if self.next_bit()? {
+                    let offset = self.offset - 1;
+                    return Err(DecodeError::new(DecodeErrorKind::NonZeroPadding, offset));
+                }
+            }
+            if self.encoding == Encoding::Bytes || !self.fill()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Decodes the next transmission, returns false once the stream is exhausted
+    pub fn visit_transmission(
+        &mut self,
+        visitor: &mut impl PacketVisitor,
+    ) -> Result<bool, DecodeError> {
+        if !self.start_transmission()? {
+            return Ok(false);
+        }
+        visit_packet(self, visitor)?;
+        self.finish_transmission()?;
+        Ok(true)
+    }
+}
+
+impl<R: Read> BitSource for StreamDecoder<R> {
+    fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn next_bit(&mut self) -> Result<bool, DecodeError> {
+        if self.remaining == 0 && !self.fill()? {
+            return Err(DecodeError::new(DecodeErrorKind::Truncated, self.offset));
+        }
+        self.remaining -= 1;
+        self.offset += 1;
+        Ok(self.buffer >> self.remaining & 1 == 1)
+    }
+}
+
+/// Yields the packet of every transmission, stops after the first error
+impl<R: Read> Iterator for StreamDecoder<R> {
+    type Item = Result<Packet, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let mut builder = PacketBuilder::default();
+        match self.visit_transmission(&mut builder) {
+            Ok(true) => Some(Ok(builder.packet.expect("a whole packet was visited"))),
+            Ok(false) => None,
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// How an operator stores the size of its sub-packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthType {
+    /// Length type id 0, the total length in bits of the sub-packets
+    TotalBits,
+    /// Length type id 1, the number of sub-packets
+    PacketCount,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// The version doesn't fit in 3 bits
+    Version(usize),
+    /// The operator type id doesn't fit in 3 bits or is the literal type id
+    TypeId(usize),
+    /// The sub-packets don't fit in the 15 bits of length type 0
+    TooManyBits(usize),
+    /// The sub-packets don't fit in the 11 bits of length type 1
+    TooManyPackets(usize),
+}
+
+impl std::fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::Version(version) => write!(f, "version {} doesn't fit in 3 bits", version),
+            EncodeError::TypeId(id) => write!(f, "type id {} isn't an operator type id", id),
+            EncodeError::TooManyBits(bits) => {
+                write!(f, "{} bits of sub-packets don't fit in 15 bits", bits)
+            }
+            EncodeError::TooManyPackets(count) => {
+                write!(f, "{} sub-packets don't fit in 11 bits", count)
+            }
         }
     }
 }
 
-pub fn part_2(packet: &Packet) -> usize {
+impl std::error::Error for EncodeError {}
+
+#[derive(Default)]
+struct BitWriter {
+    data: BitVec<Msb0, u8>,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: usize, size: usize) {
+        for i in (0..size).rev() {
+            self.data.push(value >> i & 1 == 1);
+        }
+    }
+
+    /// Pads the transmission with zeros to a whole number of bytes
+    fn into_hex_string(mut self) -> String {
+        let padding = (8 - self.data.len() % 8) % 8;
+        self.push(0, padding);
+        self.data
+            .chunks(4)
+            .map(|nibble| {
+                let value = nibble.iter().fold(0, |acc, bit| acc << 1 | *bit as u32);
+                std::char::from_digit(value, 16)
+                    .unwrap()
+                    .to_ascii_uppercase()
+            })
+            .collect()
+    }
+}
+
+fn encode_packet(
+    writer: &mut BitWriter,
+    packet: &Packet,
+    length_type: LengthType,
+) -> Result<(), EncodeError> {
+    if packet.version > 7 {
+        return Err(EncodeError::Version(packet.version));
+    }
+    writer.push(packet.version, 3);
     match &packet.packet_type {
-        PacketType::Literal(x) => *x,
+        PacketType::Literal(value) => {
+            writer.push(LITERAL_ID, 3);
+            // use as few groups as possible, 0 still needs one group
+            let groups = value.bits().div_ceil(4).max(1);
+            for group in (0..groups).rev() {
+                writer.push((group > 0) as usize, 1);
+                writer.push(value.nibble(group), 4);
+            }
+        }
         PacketType::Operator(operator, packets) => {
-            let mut packets = packets.iter().map(part_2);
-            match operator {
-                Operator::Sum => packets.sum(),
-                Operator::Product => packets.product(),
-                Operator::Min => packets.min().unwrap(),
-                Operator::Max => packets.max().unwrap(),
-                Operator::GreaterThan => (packets.next() > packets.next()) as usize,
-                Operator::LessThan => (packets.next() < packets.next()) as usize,
-                Operator::EqualtTo => (packets.next() == packets.next()) as usize,
+            if operator.0 == LITERAL_ID || operator.0 >= 1 << 3 {
+                return Err(EncodeError::TypeId(operator.0));
+            }
+            writer.push(operator.0, 3);
+            let mut sub_packets = BitWriter::default();
+            for packet in packets {
+                encode_packet(&mut sub_packets, packet, length_type)?;
+            }
+            match length_type {
+                LengthType::TotalBits => {
+                    let length = sub_packets.data.len();
+                    if length >= 1 << 15 {
+                        return Err(EncodeError::TooManyBits(length));
+                    }
+                    writer.push(0, 1);
+                    writer.push(length, 15);
+                }
+                LengthType::PacketCount => {
+                    if packets.len() >= 1 << 11 {
+                        return Err(EncodeError::TooManyPackets(packets.len()));
+                    }
+                    writer.push(1, 1);
+                    writer.push(packets.len(), 11);
+                }
+            }
+            writer.data.extend_from_bitslice(&sub_packets.data);
+        }
+    }
+    Ok(())
+}
+
+/// Encodes the packet as a hex transmission, every operator uses the given length type
+pub fn encode(packet: &Packet, length_type: LengthType) -> Result<String, EncodeError> {
+    let mut writer = BitWriter::default();
+    encode_packet(&mut writer, packet, length_type)?;
+    Ok(writer.into_hex_string())
+}
+
+pub fn parse(input: &str) -> Result<Packet, ParseError> {
+    let hex = input.trim_end();
+    decode(hex).map_err(|err| {
+        // point at the hex digit containing the bit where the error happened
+        let text = match hex.char_indices().nth(err.offset / 4) {
+            Some((i, c)) => &hex[i..i + c.len_utf8()],
+            None => hex,
+        };
+        ParseError::new(DAY, input, text, err.to_string())
+    })
+}
+
+/// How many sub-packets an operator accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arity {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    pub const fn exactly(count: usize) -> Self {
+        Self {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    pub const fn at_least(min: usize) -> Self {
+        Self { min, max: None }
+    }
+
+    pub const fn between(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max: Some(max),
+        }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && count <= self.max.unwrap_or(usize::MAX)
+    }
+}
+
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "exactly {}", max),
+            Some(max) => write!(f, "between {} and {}", self.min, max),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalErrorKind {
+    /// A literal doesn't fit in a `u128`
+    LiteralTooBig,
+    /// No operator is registered for the type id
+    Unregistered(usize),
+    /// The operator doesn't accept this number of sub-packets
+    Arity {
+        operator: String,
+        arity: Arity,
+        count: usize,
+    },
+    /// The result of the operator doesn't fit in a `u128`
+    Overflow(String),
+}
+
+impl std::fmt::Display for EvalErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalErrorKind::LiteralTooBig => write!(f, "literal doesn't fit in 128 bits"),
+            EvalErrorKind::Unregistered(id) => {
+                write!(f, "no operator is registered for type id {}", id)
+            }
+            EvalErrorKind::Arity {
+                operator,
+                arity,
+                count,
+            } => write!(
+                f,
+                "`{}` takes {} sub-packets but has {}",
+                operator, arity, count
+            ),
+            EvalErrorKind::Overflow(operator) => write!(f, "`{}` overflows", operator),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError {
+    pub kind: EvalErrorKind,
+    /// Index of each sub-packet leading from the outermost packet to the one that failed
+    pub path: Vec<usize>,
+}
+
+impl EvalError {
+    fn new(kind: EvalErrorKind) -> Self {
+        Self { kind, path: vec![] }
+    }
+
+    fn in_sub_packet(mut self, index: usize) -> Self {
+        self.path.insert(0, index);
+        self
+    }
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if !self.path.is_empty() {
+            let path = self.path.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+            write!(f, " in sub-packet {}", path.join("."))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterError {
+    /// Type id 4 always means a literal
+    LiteralId,
+    /// Type ids are stored in 3 bits
+    OutOfRange(usize),
+}
+
+impl std::fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegisterError::LiteralId => {
+                write!(f, "type id {} is reserved for literals", LITERAL_ID)
+            }
+            RegisterError::OutOfRange(id) => write!(f, "type id {} doesn't fit in 3 bits", id),
+        }
+    }
+}
+
+impl std::error::Error for RegisterError {}
+
+/// Computes the value of an operator from the values of its sub-packets, `None` means it overflowed.
+/// The values are `u128`s, a sub-packet that doesn't fit fails before the operator is called
+type EvalFn = dyn Fn(&[u128]) -> Option<u128> + Send + Sync;
+
+struct OperatorDef {
+    name: String,
+    arity: Arity,
+    eval: Box<EvalFn>,
+}
+
+/// Maps the operator type ids to how they are evaluated
+#[derive(Default)]
+pub struct OperatorRegistry {
+    operators: BTreeMap<usize, OperatorDef>,
+}
+
+impl OperatorRegistry {
+    /// Creates an empty registry, see [`OperatorRegistry::built_in`] for the puzzle's operators
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with the seven operators of the puzzle
+    pub fn built_in() -> Self {
+        let mut registry = Self::new();
+        let mut register = |operator: Operator, name, arity, eval: fn(&[u128]) -> Option<u128>| {
+            registry
+                .register(operator.0, name, arity, eval)
+                .expect("the built-in type ids are valid");
+        };
+        register(Operator::SUM, "sum", Arity::at_least(0), |values| {
+            values
+                .iter()
+                .try_fold(0u128, |acc, value| acc.checked_add(*value))
+        });
+        register(Operator::PRODUCT, "product", Arity::at_least(0), |values| {
+            values
+                .iter()
+                .try_fold(1u128, |acc, value| acc.checked_mul(*value))
+        });
+        register(Operator::MIN, "min", Arity::at_least(1), |values| {
+            values.iter().min().copied()
+        });
+        register(Operator::MAX, "max", Arity::at_least(1), |values| {
+            values.iter().max().copied()
+        });
+        register(Operator::GREATER_THAN, ">", Arity::exactly(2), |values| {
+            Some((values[0] > values[1]) as u128)
+        });
+        register(Operator::LESS_THAN, "<", Arity::exactly(2), |values| {
+            Some((values[0] < values[1]) as u128)
+        });
+        register(Operator::EQUAL_TO, "=", Arity::exactly(2), |values| {
+            Some((values[0] == values[1]) as u128)
+        });
+        registry
+    }
+
+    /// Registers an operator for the type id, replacing the one already registered.
+    /// `eval` is only called with a number of values accepted by `arity`.
+    ///
+    /// Type ids only have 3 bits and the built-in operators use all of them but the literal id,
+    /// so on top of [`OperatorRegistry::built_in`] this replaces a built-in operator.
+    /// A protocol with its own operators starts from [`OperatorRegistry::new`] instead
+    pub fn register(
+        &mut self,
+        id: usize,
+        name: impl Into<String>,
+        arity: Arity,
+        eval: impl Fn(&[u128]) -> Option<u128> + Send + Sync + 'static,
+    ) -> Result<(), RegisterError> {
+        if id == LITERAL_ID {
+            return Err(RegisterError::LiteralId);
+        }
+        if id >= 1 << 3 {
+            return Err(RegisterError::OutOfRange(id));
+        }
+        let operator = OperatorDef {
+            name: name.into(),
+            arity,
+            eval: Box::new(eval),
+        };
+        self.operators.insert(id, operator);
+        Ok(())
+    }
+
+    /// The name the operator was registered with
+    pub fn name(&self, operator: Operator) -> Option<&str> {
+        self.operators.get(&operator.0).map(|def| def.name.as_str())
+    }
+
+    /// Evaluates the expression with checked arithmetic
+    pub fn evaluate(&self, packet: &Packet) -> Result<u128, EvalError> {
+        match &packet.packet_type {
+            PacketType::Literal(value) => value
+                .to_u128()
+                .ok_or_else(|| EvalError::new(EvalErrorKind::LiteralTooBig)),
+            PacketType::Operator(operator, packets) => {
+                let id = operator.0;
+                let def = self
+                    .operators
+                    .get(&id)
+                    .ok_or_else(|| EvalError::new(EvalErrorKind::Unregistered(id)))?;
+                if !def.arity.accepts(packets.len()) {
+                    return Err(EvalError::new(EvalErrorKind::Arity {
+                        operator: def.name.clone(),
+                        arity: def.arity,
+                        count: packets.len(),
+                    }));
+                }
+                let values = packets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, packet)| self.evaluate(packet).map_err(|err| err.in_sub_packet(i)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                (def.eval)(&values)
+                    .ok_or_else(|| EvalError::new(EvalErrorKind::Overflow(def.name.clone())))
             }
         }
     }
 }
 
+lazy_static::lazy_static! {
+    static ref BUILT_IN_OPERATORS: OperatorRegistry = OperatorRegistry::built_in();
+}
+
+/// Evaluates the expression with the built-in operators
+pub fn evaluate(packet: &Packet) -> Result<u128, EvalError> {
+    BUILT_IN_OPERATORS.evaluate(packet)
+}
+
+pub fn part_1(packet: &Packet) -> usize {
+    let sub_packets = match &packet.packet_type {
+        PacketType::Literal(_) => 0,
+        PacketType::Operator(_op, packets) => packets.iter().map(part_1).sum::<usize>(),
+    };
+    packet.version + sub_packets
+}
+
+/// Every value is a `u128`, so a literal too big for one fails the evaluation
+/// even under the operators that could compare it, like `max` or `<`
+pub fn part_2(packet: &Packet) -> Result<u128, EvalError> {
+    evaluate(packet)
+}
+
 #[cfg(test)]
 mod tests {
+    use indoc::indoc;
+
     #[test]
     pub fn parse() {
-        use super::{parse, Operator, Packet, PacketType};
+        use super::{parse, Operator, Packet, PacketType, Value};
 
-        assert_eq!(parse("D2FE28"), Packet::new(6, PacketType::Literal(2021)));
+        assert_eq!(
+            parse("D2FE28").unwrap(),
+            Packet::new(6, PacketType::Literal(Value::Small(2021)))
+        );
 
         assert_eq!(
-            parse("38006F45291200"),
+            parse("38006F45291200").unwrap(),
             Packet::new(
                 1,
                 PacketType::Operator(
-                    Operator::LessThan,
+                    Operator::LESS_THAN,
                     vec![
-                        Packet::new(6, PacketType::Literal(10)),
-                        Packet::new(2, PacketType::Literal(20)),
+                        Packet::new(6, PacketType::Literal(Value::Small(10))),
+                        Packet::new(2, PacketType::Literal(Value::Small(20))),
                     ],
                 ),
             )
         );
 
         assert_eq!(
-            parse("EE00D40C823060"),
+            parse("EE00D40C823060").unwrap(),
             Packet::new(
                 7,
                 PacketType::Operator(
-                    Operator::Max,
+                    Operator::MAX,
                     vec![
-                        Packet::new(2, PacketType::Literal(1)),
-                        Packet::new(4, PacketType::Literal(2)),
-                        Packet::new(1, PacketType::Literal(3)),
+                        Packet::new(2, PacketType::Literal(Value::Small(1))),
+                        Packet::new(4, PacketType::Literal(Value::Small(2))),
+                        Packet::new(1, PacketType::Literal(Value::Small(3))),
                     ],
                 ),
             )
         );
 
         assert_eq!(
-            parse("8A004A801A8002F478"),
+            parse("8A004A801A8002F478").unwrap(),
             Packet::new(
                 4,
                 PacketType::Operator(
-                    Operator::Min,
+                    Operator::MIN,
                     vec![Packet::new(
                         1,
                         PacketType::Operator(
-                            Operator::Min,
+                            Operator::MIN,
                             vec![Packet::new(
                                 5,
                                 PacketType::Operator(
-                                    Operator::Min,
-                                    vec![Packet::new(6, PacketType::Literal(15))],
+                                    Operator::MIN,
+                                    vec![Packet::new(6, PacketType::Literal(Value::Small(15)))],
                                 ),
                             )],
                         ),
@@ -211,29 +1102,29 @@ mod tests {
         );
 
         assert_eq!(
-            parse("620080001611562C8802118E34"),
+            parse("620080001611562C8802118E34").unwrap(),
             Packet::new(
                 3,
                 PacketType::Operator(
-                    Operator::Sum,
+                    Operator::SUM,
                     vec![
                         Packet::new(
                             0,
                             PacketType::Operator(
-                                Operator::Sum,
+                                Operator::SUM,
                                 vec![
-                                    Packet::new(0, PacketType::Literal(10)),
-                                    Packet::new(5, PacketType::Literal(11)),
+                                    Packet::new(0, PacketType::Literal(Value::Small(10))),
+                                    Packet::new(5, PacketType::Literal(Value::Small(11))),
                                 ],
                             ),
                         ),
                         Packet::new(
                             1,
                             PacketType::Operator(
-                                Operator::Sum,
+                                Operator::SUM,
                                 vec![
-                                    Packet::new(0, PacketType::Literal(12)),
-                                    Packet::new(3, PacketType::Literal(13)),
+                                    Packet::new(0, PacketType::Literal(Value::Small(12))),
+                                    Packet::new(3, PacketType::Literal(Value::Small(13))),
                                 ],
                             ),
                         ),
@@ -243,27 +1134,441 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_error() {
+        use super::parse;
+
+        let err = parse("D2FG28").unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (16, 1, 4));
+        assert_eq!(err.text, "G");
+        assert_eq!(err.reason, "bad hex digit `G` at bit 12");
+    }
+
+    #[test]
+    pub fn decode_error() {
+        use super::{decode, DecodeError, DecodeErrorKind};
+
+        let assert_error = |hex, kind, offset, path: &[usize]| {
+            let expected = DecodeError {
+                kind,
+                offset,
+                path: path.to_vec(),
+            };
+            assert_eq!(decode(hex), Err(expected));
+        };
+
+        assert_error("D2FE", DecodeErrorKind::Truncated, 16, &[]);
+        assert_error("D2FE29", DecodeErrorKind::NonZeroPadding, 23, &[]);
+        assert_error("D2XE28", DecodeErrorKind::BadHexDigit('X'), 8, &[]);
+        // the second literal of 38006F45291200 is cut short
+        assert_error("38006F4529", DecodeErrorKind::Truncated, 40, &[1]);
+        // the innermost literal of 8A004A801A8002F478 is cut short
+        assert_error(
+            "8A004A801A8002F4",
+            DecodeErrorKind::Truncated,
+            64,
+            &[0, 0, 0],
+        );
+        // the first operator of 38006F45291200 declares 26 bits instead of 27
+        assert_error(
+            "38006B45291200",
+            DecodeErrorKind::LengthMismatch {
+                expected: 26,
+                actual: 27,
+            },
+            22,
+            &[],
+        );
+
+        let err = decode("8A004A801A8002F4").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "truncated packet at bit 64 in sub-packet 0.0.0"
+        );
+    }
+
+    #[test]
+    pub fn stream() {
+        use super::{
+            encode, parse, DecodeError, DecodeErrorKind, Encoding, LengthType, Operator,
+            PacketVisitor, StreamDecoder, Value,
+        };
+
+        const TRANSMISSIONS: &str = indoc! {"
+            8A004A801A8002F478
+            620080001611562C8802118E34
+
+            C0015000016115A2E0802F182340
+            A0016C880162017C3686B18A3D4780
+        "};
+
+        #[derive(Default)]
+        struct VersionSum {
+            sum: usize,
+            depth: usize,
+        }
+
+        impl PacketVisitor for VersionSum {
+            fn enter_operator(&mut self, version: usize, _operator: Operator) {
+                self.sum += version;
+                self.depth += 1;
+            }
+
+            fn literal(&mut self, version: usize, _value: Value) {
+                self.sum += version;
+            }
+
+            fn exit_operator(&mut self, _operator: Operator) {
+                self.depth -= 1;
+            }
+        }
+
+        let mut decoder = StreamDecoder::new(TRANSMISSIONS.as_bytes(), Encoding::Hex);
+        let mut sums = vec![];
+        loop {
+            let mut visitor = VersionSum::default();
+            if !decoder.visit_transmission(&mut visitor).unwrap() {
+                break;
+            }
+            assert_eq!(visitor.depth, 0);
+            sums.push(visitor.sum);
+        }
+        assert_eq!(sums, [16, 12, 23, 31]);
+
+        let packets = TRANSMISSIONS
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| parse(line).unwrap())
+            .collect::<Vec<_>>();
+        let decoded = StreamDecoder::new(TRANSMISSIONS.as_bytes(), Encoding::Hex)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, packets);
+
+        let bytes = packets
+            .iter()
+            .flat_map(|packet| {
+                let hex = encode(packet, LengthType::PacketCount).unwrap();
+                (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let decoded = StreamDecoder::new(bytes.as_slice(), Encoding::Bytes)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(decoded, packets);
+
+        // the offsets count the bits since the start of the stream
+        let mut decoder = StreamDecoder::new("D2FE28\nD2FE29\nD2FE28".as_bytes(), Encoding::Hex);
+        assert!(decoder.next().unwrap().is_ok());
+        assert_eq!(
+            decoder.next(),
+            Some(Err(DecodeError {
+                kind: DecodeErrorKind::NonZeroPadding,
+                offset: 47,
+                path: vec![],
+            }))
+        );
+        assert_eq!(decoder.next(), None);
+    }
+
+    #[test]
+    pub fn encode() {
+        use super::{
+            encode, parse, EncodeError, LengthType, Operator, Packet, PacketType, Value, LITERAL_ID,
+        };
+
+        let assert_encode = |hex: &str, length_type| {
+            let packet = parse(hex).unwrap();
+            assert_eq!(encode(&packet, length_type).unwrap(), hex);
+        };
+        assert_encode("D2FE28", LengthType::TotalBits);
+        assert_encode("38006F45291200", LengthType::TotalBits);
+        assert_encode("EE00D40C823060", LengthType::PacketCount);
+
+        let packet = Packet::new(8, PacketType::Literal(Value::Small(0)));
+        assert_eq!(
+            encode(&packet, LengthType::TotalBits),
+            Err(EncodeError::Version(8))
+        );
+        let packet = Packet::new(0, PacketType::Operator(Operator(LITERAL_ID), vec![]));
+        assert_eq!(
+            encode(&packet, LengthType::TotalBits),
+            Err(EncodeError::TypeId(LITERAL_ID))
+        );
+    }
+
+    mod round_trip {
+        use num_bigint::BigUint;
+        use proptest::prelude::*;
+
+        use super::super::{
+            encode, parse, LengthType, Operator, Packet, PacketType, Value, LITERAL_ID,
+        };
+
+        fn operator() -> impl Strategy<Value = Operator> {
+            (0..8usize)
+                .prop_filter("4 is the literal type id", |id| *id != LITERAL_ID)
+                .prop_map(Operator)
+        }
+
+        fn packet() -> impl Strategy<Value = Packet> {
+            let value = prop_oneof![
+                any::<u128>().prop_map(Value::from),
+                prop::collection::vec(any::<u32>(), 0..8)
+                    .prop_map(|digits| Value::from(BigUint::new(digits))),
+            ];
+            let literal = (0..8usize, value)
+                .prop_map(|(version, value)| Packet::new(version, PacketType::Literal(value)));
+            literal.prop_recursive(4, 64, 8, |inner| {
+                (0..8usize, operator(), prop::collection::vec(inner, 0..8)).prop_map(
+                    |(version, operator, packets)| {
+                        Packet::new(version, PacketType::Operator(operator, packets))
+                    },
+                )
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn parse_encoded(
+                packet in packet(),
+                length_type in prop_oneof![Just(LengthType::TotalBits), Just(LengthType::PacketCount)],
+            ) {
+                let hex = encode(&packet, length_type).unwrap();
+                prop_assert_eq!(parse(&hex).unwrap(), packet);
+            }
+        }
+    }
+
+    #[test]
+    pub fn display() {
+        use super::parse;
+
+        let packet = parse("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(packet.to_string(), "(= (sum 1 3) (product 2 2))");
+        let packet = parse("EE00D40C823060").unwrap();
+        assert_eq!(format!("{:#}", packet), "(max[v7] 1[v2] 2[v4] 3[v1])");
+        let packet = parse("8A004A801A8002F478").unwrap();
+        assert_eq!(packet.to_string(), "(min (min (min 15)))");
+    }
+
+    #[test]
+    pub fn json() {
+        use super::parse;
+
+        let packet = parse("38006F45291200").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&packet.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": 1,
+                "packet_type": {
+                    "operator": {
+                        "type_id": 6,
+                        "name": "<",
+                        "packets": [
+                            { "version": 6, "packet_type": { "literal": 10 } },
+                            { "version": 2, "packet_type": { "literal": 20 } },
+                        ]
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    pub fn big_literals() {
+        use num_bigint::BigUint;
+
+        use super::{
+            encode, evaluate, parse, part_2, EvalErrorKind, LengthType, Operator, Packet,
+            PacketType, Value,
+        };
+
+        let big = Value::from(BigUint::from(u128::MAX) + 1u8);
+        assert!(matches!(big, Value::Big(_)));
+        let packet = Packet::new(0, PacketType::Literal(big));
+        let hex = encode(&packet, LengthType::TotalBits).unwrap();
+        let decoded = parse(&hex).unwrap();
+        assert_eq!(decoded, packet);
+        assert_eq!(
+            decoded.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(
+            decoded.to_json(),
+            r#"{
+  "version": 0,
+  "packet_type": {
+    "literal": "340282366920938463463374607431768211456"
+  }
+}"#
+        );
+        assert_eq!(
+            evaluate(&decoded).unwrap_err().kind,
+            EvalErrorKind::LiteralTooBig
+        );
+        // even though `max` could compare it
+        let one = Packet::new(0, PacketType::Literal(Value::Small(1)));
+        let max = Packet::new(0, PacketType::Operator(Operator::MAX, vec![one, decoded]));
+        let err = part_2(&max).unwrap_err();
+        assert_eq!(
+            (err.kind, err.path),
+            (EvalErrorKind::LiteralTooBig, vec![1])
+        );
+
+        let packet = Packet::new(0, PacketType::Literal(Value::Small(u128::MAX)));
+        let hex = encode(&packet, LengthType::TotalBits).unwrap();
+        assert_eq!(evaluate(&parse(&hex).unwrap()), Ok(u128::MAX));
+    }
+
+    #[test]
+    pub fn evaluate_error() {
+        use super::{
+            evaluate, Arity, EvalError, EvalErrorKind, Operator, Packet, PacketType, Value,
+        };
+
+        let literal = |value| Packet::new(0, PacketType::Literal(Value::Small(value)));
+        let operator = |operator, packets| Packet::new(0, PacketType::Operator(operator, packets));
+
+        let packet = operator(
+            Operator::PRODUCT,
+            vec![
+                literal(2),
+                operator(Operator::SUM, vec![literal(u128::MAX), literal(1)]),
+            ],
+        );
+        let err = evaluate(&packet).unwrap_err();
+        assert_eq!(
+            err,
+            EvalError {
+                kind: EvalErrorKind::Overflow("sum".to_string()),
+                path: vec![1],
+            }
+        );
+        assert_eq!(err.to_string(), "`sum` overflows in sub-packet 1");
+
+        let packet = operator(Operator::PRODUCT, vec![literal(u128::MAX), literal(2)]);
+        assert_eq!(
+            evaluate(&packet).unwrap_err().kind,
+            EvalErrorKind::Overflow("product".to_string())
+        );
+
+        let packet = operator(Operator::MIN, vec![]);
+        assert_eq!(
+            evaluate(&packet).unwrap_err().kind,
+            EvalErrorKind::Arity {
+                operator: "min".to_string(),
+                arity: Arity::at_least(1),
+                count: 0,
+            }
+        );
+
+        let packet = operator(
+            Operator::GREATER_THAN,
+            vec![literal(1), literal(2), literal(3)],
+        );
+        assert_eq!(
+            evaluate(&packet).unwrap_err().to_string(),
+            "`>` takes exactly 2 sub-packets but has 3"
+        );
+    }
+
+    #[test]
+    pub fn operator_registry() {
+        use super::{
+            Arity, EvalErrorKind, Operator, OperatorRegistry, Packet, PacketType, RegisterError,
+            Value, LITERAL_ID,
+        };
+
+        let greater_than = |a, b| {
+            let literal = |value| Packet::new(0, PacketType::Literal(Value::Small(value)));
+            let packets = vec![literal(a), literal(b)];
+            Packet::new(0, PacketType::Operator(Operator::GREATER_THAN, packets))
+        };
+        let small = greater_than(2, 10);
+        let big = greater_than(2, 200);
+
+        let mut registry = OperatorRegistry::built_in();
+        assert_eq!(registry.evaluate(&small), Ok(0));
+        registry
+            .register(5, "pow", Arity::exactly(2), |values| {
+                let exponent = u32::try_from(values[1]).ok()?;
+                values[0].checked_pow(exponent)
+            })
+            .unwrap();
+        assert_eq!(registry.evaluate(&small), Ok(1024));
+        assert_eq!(small.display(&registry).to_string(), "(pow 2 10)");
+        assert_eq!(small.to_string(), "(> 2 10)");
+        let json: serde_json::Value = serde_json::from_str(&small.to_json_with(&registry)).unwrap();
+        assert_eq!(json["packet_type"]["operator"]["name"], "pow");
+        assert_eq!(
+            registry.evaluate(&big).unwrap_err().kind,
+            EvalErrorKind::Overflow("pow".to_string())
+        );
+
+        let noop = |_: &[u128]| Some(0);
+        assert_eq!(
+            registry.register(LITERAL_ID, "noop", Arity::exactly(0), noop),
+            Err(RegisterError::LiteralId)
+        );
+        assert_eq!(
+            registry.register(8, "noop", Arity::exactly(0), noop),
+            Err(RegisterError::OutOfRange(8))
+        );
+
+        // A protocol with only its own operators
+        let mut registry = OperatorRegistry::new();
+        assert_eq!(
+            registry.evaluate(&small).unwrap_err().kind,
+            EvalErrorKind::Unregistered(5)
+        );
+        assert_eq!(small.display(&registry).to_string(), "(op5 2 10)");
+        registry
+            .register(3, "avg", Arity::at_least(1), |values| {
+                let sum = values
+                    .iter()
+                    .try_fold(0u128, |acc, v| acc.checked_add(*v))?;
+                Some(sum / values.len() as u128)
+            })
+            .unwrap();
+        let literal = |value| Packet::new(0, PacketType::Literal(Value::Small(value)));
+        let avg = Packet::new(
+            0,
+            PacketType::Operator(Operator(3), vec![literal(2), literal(4), literal(9)]),
+        );
+        assert_eq!(avg.display(&registry).to_string(), "(avg 2 4 9)");
+        assert_eq!(avg.to_string(), "(max 2 4 9)");
+        assert_eq!(registry.evaluate(&avg), Ok(5));
+    }
+
     #[test]
     pub fn part_1() {
         use super::{parse, part_1};
 
-        assert_eq!(part_1(&parse("8A004A801A8002F478")), 16);
-        assert_eq!(part_1(&parse("620080001611562C8802118E34")), 12);
-        assert_eq!(part_1(&parse("C0015000016115A2E0802F182340")), 23);
-        assert_eq!(part_1(&parse("A0016C880162017C3686B18A3D4780")), 31);
+        assert_eq!(part_1(&parse("8A004A801A8002F478").unwrap()), 16);
+        assert_eq!(part_1(&parse("620080001611562C8802118E34").unwrap()), 12);
+        assert_eq!(part_1(&parse("C0015000016115A2E0802F182340").unwrap()), 23);
+        assert_eq!(
+            part_1(&parse("A0016C880162017C3686B18A3D4780").unwrap()),
+            31
+        );
     }
 
     #[test]
     pub fn part_2() {
         use super::{parse, part_2};
 
-        assert_eq!(part_2(&parse("C200B40A82")), 3);
-        assert_eq!(part_2(&parse("04005AC33890")), 54);
-        assert_eq!(part_2(&parse("880086C3E88112")), 7);
-        assert_eq!(part_2(&parse("CE00C43D881120")), 9);
-        assert_eq!(part_2(&parse("D8005AC2A8F0")), 1);
-        assert_eq!(part_2(&parse("F600BC2D8F")), 0);
-        assert_eq!(part_2(&parse("9C005AC2F8F0")), 0);
-        assert_eq!(part_2(&parse("9C0141080250320F1802104A08")), 1);
+        assert_eq!(part_2(&parse("C200B40A82").unwrap()), Ok(3));
+        assert_eq!(part_2(&parse("04005AC33890").unwrap()), Ok(54));
+        assert_eq!(part_2(&parse("880086C3E88112").unwrap()), Ok(7));
+        assert_eq!(part_2(&parse("CE00C43D881120").unwrap()), Ok(9));
+        assert_eq!(part_2(&parse("D8005AC2A8F0").unwrap()), Ok(1));
+        assert_eq!(part_2(&parse("F600BC2D8F").unwrap()), Ok(0));
+        assert_eq!(part_2(&parse("9C005AC2F8F0").unwrap()), Ok(0));
+        assert_eq!(part_2(&parse("9C0141080250320F1802104A08").unwrap()), Ok(1));
     }
 }
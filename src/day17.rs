@@ -1,10 +1,21 @@
 use serde_scan::scan;
 
-type TargetArea = (i32, i32, i32, i32);
+use crate::error::ParseError;
 
-pub fn parse(input: &str) -> TargetArea {
-    let input = input.trim();
-    scan!("target area: x={}..{}, y={}..{}" <- input).expect("failed to parse")
+const DAY: u8 = 17;
+
+pub type TargetArea = (i32, i32, i32, i32);
+
+pub fn parse(input: &str) -> Result<TargetArea, ParseError> {
+    let target = input.trim();
+    scan!("target area: x={}..{}, y={}..{}" <- target).map_err(|_| {
+        ParseError::new(
+            DAY,
+            input,
+            target,
+            "expected `target area: x=<min>..<max>, y=<min>..<max>`",
+        )
+    })
 }
 
 fn simulate(start_velocity: (i32, i32), target_area: &TargetArea) -> Option<i32> {
@@ -64,10 +75,17 @@ mod tests {
         target area: x=20..30, y=-10..-5
     "};
 
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("\ntarget area: x=20..30").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.text, "target area: x=20..30");
+    }
+
     #[test]
     pub fn simulate() {
         use super::simulate;
-        let target = super::parse(INPUTS);
+        let target = super::parse(INPUTS).unwrap();
 
         assert_eq!(simulate((7, 2), &target), Some(3));
         assert_eq!(simulate((6, 3), &target), Some(6));
@@ -79,14 +97,14 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 45);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 112);
     }
@@ -1,6 +1,36 @@
-use std::cell::RefCell;
+use std::{iter::Peekable, str::CharIndices};
 
-type Data = Vec<SnaifishNumber>;
+use crate::error::{parse_number, ParseError};
+
+const DAY: u8 = 18;
+
+pub type Data = Vec<SnaifishNumber>;
+
+/// The operations needed by the puzzle, implemented by both representations of a snailfish number
+/// so they can be checked against each other
+pub trait Snailfish: Clone + PartialEq + Sized {
+    fn parse(str: &str) -> Result<Self, ParseError>;
+    /// Pairs both numbers without reducing the result
+    fn add(self, other: Self) -> Self;
+    /// Explodes the leftmost pair nested inside four pairs, returns false if there was none
+    fn explode(&mut self) -> bool;
+    /// Splits the leftmost number of 10 or more, returns false if there was none
+    fn split(&mut self) -> bool;
+    fn magnitude(&self) -> u16;
+
+    fn reduce(&mut self) {
+        loop {
+            if !self.explode() && !self.split() {
+                break;
+            }
+        }
+    }
+}
+
+fn unexpected_character(str: &str, i: usize, c: char) -> ParseError {
+    let text = &str[i..i + c.len_utf8()];
+    ParseError::new(DAY, str, text, "unexpected character")
+}
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct SnaifishNumber {
@@ -9,62 +39,39 @@ pub struct SnaifishNumber {
 }
 
 impl SnaifishNumber {
-    fn parse(str: &str) -> Self {
-        let mut values = vec![];
-        let mut depths = vec![];
-
-        let mut depth = 0;
-        let mut number = String::new();
-        let mut chars = str.chars();
-        loop {
-            match chars.next() {
-                Some('[') => depth += 1,
-                Some(c) if c.is_digit(10) => {
-                    number = format!("{}{}", number, c);
-                }
-                c => {
-                    if !number.is_empty() {
-                        values.push(
-                            number
-                                .parse()
-                                .unwrap_or_else(|_| panic!("failed to parse number {}", number)),
-                        );
-                        number = String::new();
-                        depths.push(depth);
-                    }
-
-                    if let Some(']') = c {
-                        depth -= 1;
-                    }
-
-                    if c.is_none() {
-                        return Self { values, depths };
-                    }
-                }
+    fn push_tree(&mut self, tree: &Tree, depth: u8) {
+        match tree {
+            Tree::Number(value) => {
+                self.values.push(*value);
+                self.depths.push(depth);
+            }
+            Tree::Pair(left, right) => {
+                self.push_tree(left, depth + 1);
+                self.push_tree(right, depth + 1);
             }
         }
     }
 
-    fn add(self, other: SnaifishNumber) -> SnaifishNumber {
-        let mut values = self.values;
-        values.extend(other.values);
-        let mut depths = self.depths;
-        depths.extend(other.depths);
-        for d in depths.iter_mut() {
-            *d += 1;
-        }
-        SnaifishNumber { values, depths }
-    }
-
-    fn reduce(&mut self) {
-        loop {
-            if !self.explode() && !self.split() {
-                break;
-            }
+    /// Writes the element starting at `i`, an element is a number if it's at `depth` or a pair otherwise
+    fn fmt_element(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        i: &mut usize,
+        depth: u8,
+    ) -> std::fmt::Result {
+        if self.depths[*i] == depth {
+            *i += 1;
+            return write!(f, "{}", self.values[*i - 1]);
         }
+        write!(f, "[")?;
+        self.fmt_element(f, i, depth + 1)?;
+        write!(f, ",")?;
+        self.fmt_element(f, i, depth + 1)?;
+        write!(f, "]")
     }
 
-    fn explode(&mut self) -> bool {
+    /// Explodes the leftmost pair nested inside four pairs and returns the index of its left value
+    fn explode_index(&mut self) -> Option<usize> {
         for i in 0..self.depths.len() {
             if self.depths[i] <= 4 {
                 continue;
@@ -83,27 +90,113 @@ impl SnaifishNumber {
             self.values.remove(i + 1);
             self.depths[i] -= 1;
             self.depths.remove(i + 1);
-            return true;
+            return Some(i);
         }
-        false
+        None
     }
 
-    fn split(&mut self) -> bool {
+    /// Splits the leftmost number of 10 or more and returns it
+    fn split_value(&mut self) -> Option<u16> {
         for i in 0..self.depths.len() {
             if self.values[i] < 10 {
                 continue;
             }
 
-            let value = self.values[i] as f32;
+            let original = self.values[i];
+            let value = original as f32;
             self.values.remove(i);
             self.values.insert(i, (value / 2.).floor() as u16);
             self.values.insert(i + 1, (value / 2.).ceil() as u16);
 
             self.depths[i] += 1;
             self.depths.insert(i + 1, self.depths[i]);
-            return true;
+            return Some(original);
+        }
+        None
+    }
+
+    /// Adds both numbers and yields every step of the reduction, starting with the addition
+    pub fn trace_add(self, other: SnaifishNumber) -> Reduction {
+        Reduction {
+            number: Snailfish::add(self, other),
+            addition: true,
+            done: false,
+        }
+    }
+}
+
+impl From<&Tree> for SnaifishNumber {
+    fn from(tree: &Tree) -> Self {
+        let mut number = SnaifishNumber {
+            values: vec![],
+            depths: vec![],
+        };
+        number.push_tree(tree, 0);
+        number
+    }
+}
+
+impl std::fmt::Display for SnaifishNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_element(f, &mut 0, 0)
+    }
+}
+
+impl std::str::FromStr for SnaifishNumber {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as Snailfish>::parse(s)
+    }
+}
+
+/// Adds and reduces the result
+impl std::ops::Add for SnaifishNumber {
+    type Output = SnaifishNumber;
+
+    fn add(self, rhs: SnaifishNumber) -> Self::Output {
+        let mut result = Snailfish::add(self, rhs);
+        result.reduce();
+        result
+    }
+}
+
+/// Adds every number in order, `None` if there are no numbers since there's no snailfish zero
+impl std::iter::Sum<SnaifishNumber> for Option<SnaifishNumber> {
+    fn sum<I: Iterator<Item = SnaifishNumber>>(iter: I) -> Self {
+        iter.reduce(|a, b| a + b)
+    }
+}
+
+impl<'a> std::iter::Sum<&'a SnaifishNumber> for Option<SnaifishNumber> {
+    fn sum<I: Iterator<Item = &'a SnaifishNumber>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+impl Snailfish for SnaifishNumber {
+    /// The number is parsed as a tree first to make sure every pair has two elements
+    fn parse(str: &str) -> Result<Self, ParseError> {
+        <Tree as Snailfish>::parse(str).map(|tree| SnaifishNumber::from(&tree))
+    }
+
+    fn add(self, other: SnaifishNumber) -> SnaifishNumber {
+        let mut values = self.values;
+        values.extend(other.values);
+        let mut depths = self.depths;
+        depths.extend(other.depths);
+        for d in depths.iter_mut() {
+            *d += 1;
         }
-        false
+        SnaifishNumber { values, depths }
+    }
+
+    fn explode(&mut self) -> bool {
+        self.explode_index().is_some()
+    }
+
+    fn split(&mut self) -> bool {
+        self.split_value().is_some()
     }
 
     fn magnitude(&self) -> u16 {
@@ -127,88 +220,238 @@ impl SnaifishNumber {
     }
 }
 
-#[derive(Debug, Clone)]
-enum Tree {
-    Number(usize),
-    Pair(RefCell<Box<Tree>>, RefCell<Box<Tree>>),
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Addition,
+    /// The pair whose left value is at `index` in the flat list of values exploded
+    Explode {
+        index: usize,
+    },
+    Split {
+        value: u16,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReductionStep {
+    pub action: Action,
+    /// The number after the action
+    pub number: SnaifishNumber,
+}
+
+/// Formatted like the puzzle text
+impl std::fmt::Display for ReductionStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self.action {
+            Action::Addition => "after addition:",
+            Action::Explode { .. } => "after explode:",
+            Action::Split { .. } => "after split:",
+        };
+        write!(f, "{:<16}{}", label, self.number)
+    }
+}
+
+/// Iterator over the steps of a reduction, the last step holds the reduced number
+pub struct Reduction {
+    number: SnaifishNumber,
+    addition: bool,
+    done: bool,
+}
+
+impl Iterator for Reduction {
+    type Item = ReductionStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let action = if std::mem::take(&mut self.addition) {
+            Action::Addition
+        } else if self.done {
+            return None;
+        } else if let Some(index) = self.number.explode_index() {
+            Action::Explode { index }
+        } else if let Some(value) = self.number.split_value() {
+            Action::Split { value }
+        } else {
+            self.done = true;
+            return None;
+        };
+        Some(ReductionStep {
+            action,
+            number: self.number.clone(),
+        })
+    }
+}
+
+/// A snailfish number stored as a binary tree
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Tree {
+    Number(u16),
+    Pair(Box<Tree>, Box<Tree>),
+}
+
+impl std::str::FromStr for Tree {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as Snailfish>::parse(s)
+    }
 }
 
 impl std::fmt::Display for Tree {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Number(arg0) => write!(f, "{}", arg0),
-            Self::Pair(arg0, arg1) => write!(f, "[{},{}]", arg0.borrow(), arg1.borrow(),),
+            Self::Pair(arg0, arg1) => write!(f, "[{},{}]", arg0, arg1),
         }
     }
 }
 
-#[allow(unused)]
 impl Tree {
-    fn parse<I>(chars: &mut I) -> Self
-    where
-        I: Iterator<Item = char>,
-    {
+    fn parse_node(str: &str, chars: &mut Peekable<CharIndices>) -> Result<Self, ParseError> {
         match chars.next() {
-            Some('[') => Tree::Pair(
-                RefCell::new(Box::new(Tree::parse(chars))),
-                RefCell::new(Box::new(Tree::parse(chars))),
-            ),
-            Some(',' | ']') => Tree::parse(chars),
-            c => {
-                let mut c = c;
-                let mut number = String::new();
-                loop {
-                    match c {
-                        Some(c) if c.is_digit(10) => number = format!("{}{}", number, c),
-                        _ => {
-                            let result = number
-                                .parse()
-                                .unwrap_or_else(|_| panic!("failed to parse number {}", number));
-                            return Tree::Number(result);
-                        }
-                    }
-                    c = chars.next();
+            Some((_, '[')) => {
+                let left = Tree::parse_node(str, chars)?;
+                Tree::expect(str, chars, ',')?;
+                let right = Tree::parse_node(str, chars)?;
+                Tree::expect(str, chars, ']')?;
+                Ok(Tree::Pair(Box::new(left), Box::new(right)))
+            }
+            Some((start, c)) if c.is_ascii_digit() => {
+                let mut end = start + 1;
+                while let Some((i, _)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end = i + 1;
                 }
+                Ok(Tree::Number(parse_number(DAY, str, &str[start..end])?))
             }
+            Some((i, c)) => Err(unexpected_character(str, i, c)),
+            None => Err(ParseError::new(
+                DAY,
+                str,
+                str,
+                "expected a number or a pair",
+            )),
         }
     }
 
-    fn add(&self, other: &Tree) -> Tree {
-        Tree::Pair(
-            RefCell::new(Box::new(self.clone())),
-            RefCell::new(Box::new(other.clone())),
-        )
+    fn expect(
+        str: &str,
+        chars: &mut Peekable<CharIndices>,
+        expected: char,
+    ) -> Result<(), ParseError> {
+        match chars.next() {
+            Some((_, c)) if c == expected => Ok(()),
+            Some((i, c)) => Err(unexpected_character(str, i, c)),
+            None => Err(ParseError::new(DAY, str, str, "unclosed pair")),
+        }
     }
 
-    fn explode(&mut self, depth: usize) -> bool {
-        if depth > 4 {
-            // TODO explode!
-            true
-        } else {
-            match self {
-                Tree::Number(_) => false,
-                Tree::Pair(left, right) => {
-                    left.borrow_mut().explode(depth + 1) || right.borrow_mut().explode(depth + 1)
-                }
+    /// Explodes the leftmost pair at the given depth or deeper,
+    /// returns the values that still need to be added to the left and right of the pair
+    fn explode_at(&mut self, depth: usize) -> Option<(Option<u16>, Option<u16>)> {
+        let (left, right) = match self {
+            Tree::Number(_) => return None,
+            Tree::Pair(left, right) => (left, right),
+        };
+        if depth >= 4 {
+            if let (Tree::Number(l), Tree::Number(r)) = (left.as_ref(), right.as_ref()) {
+                let carry = (Some(*l), Some(*r));
+                *self = Tree::Number(0);
+                return Some(carry);
+            }
+        }
+        if let Some((carry_left, carry_right)) = left.explode_at(depth + 1) {
+            if let Some(value) = carry_right {
+                right.add_leftmost(value);
             }
+            return Some((carry_left, None));
+        }
+        if let Some((carry_left, carry_right)) = right.explode_at(depth + 1) {
+            if let Some(value) = carry_left {
+                left.add_rightmost(value);
+            }
+            return Some((None, carry_right));
+        }
+        None
+    }
+
+    fn add_leftmost(&mut self, value: u16) {
+        match self {
+            Tree::Number(n) => *n += value,
+            Tree::Pair(left, _) => left.add_leftmost(value),
+        }
+    }
+
+    fn add_rightmost(&mut self, value: u16) {
+        match self {
+            Tree::Number(n) => *n += value,
+            Tree::Pair(_, right) => right.add_rightmost(value),
         }
     }
 }
 
-pub fn parse(input: &str) -> Data {
-    input.lines().map(SnaifishNumber::parse).collect()
+impl Snailfish for Tree {
+    fn parse(str: &str) -> Result<Self, ParseError> {
+        let mut chars = str.char_indices().peekable();
+        let tree = Tree::parse_node(str, &mut chars)?;
+        match chars.next() {
+            Some((i, c)) => Err(unexpected_character(str, i, c)),
+            None => Ok(tree),
+        }
+    }
+
+    fn add(self, other: Tree) -> Tree {
+        Tree::Pair(Box::new(self), Box::new(other))
+    }
+
+    fn explode(&mut self) -> bool {
+        self.explode_at(0).is_some()
+    }
+
+    fn split(&mut self) -> bool {
+        match self {
+            Tree::Number(value) if *value >= 10 => {
+                let half = *value / 2;
+                *self = Tree::Pair(
+                    Box::new(Tree::Number(half)),
+                    Box::new(Tree::Number(*value - half)),
+                );
+                true
+            }
+            Tree::Number(_) => false,
+            Tree::Pair(left, right) => left.split() || right.split(),
+        }
+    }
+
+    fn magnitude(&self) -> u16 {
+        match self {
+            Tree::Number(value) => *value,
+            Tree::Pair(left, right) => 3 * left.magnitude() + 2 * right.magnitude(),
+        }
+    }
 }
 
-pub fn part_1(input: &Data) -> u16 {
-    let mut result = input.first().unwrap().clone();
-    for tree in input.iter().skip(1) {
-        result = result.add(tree.clone());
+/// Parses every line using the given representation
+pub fn parse_as<S: Snailfish>(input: &str) -> Result<Vec<S>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| S::parse(l).map_err(|err| err.at_line(i + 1)))
+        .collect()
+}
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    parse_as(input)
+}
+
+pub fn solve_part_1<S: Snailfish>(input: &[S]) -> u16 {
+    let result = input.iter().cloned().reduce(|a, b| {
+        let mut result = a.add(b);
         result.reduce();
-    }
-    result.magnitude()
+        result
+    });
+    result.map_or(0, |result| result.magnitude())
 }
 
-pub fn part_2(input: &Data) -> u16 {
+pub fn solve_part_2<S: Snailfish>(input: &[S]) -> u16 {
     let mut max = 0;
     for a in input.iter() {
         for b in input.iter() {
@@ -222,11 +465,19 @@ pub fn part_2(input: &Data) -> u16 {
     max
 }
 
+pub fn part_1(input: &Data) -> u16 {
+    solve_part_1(input)
+}
+
+pub fn part_2(input: &Data) -> u16 {
+    solve_part_2(input)
+}
+
 #[cfg(test)]
 mod tests {
     use indoc::indoc;
 
-    use super::{SnaifishNumber, Tree};
+    use super::{Action, SnaifishNumber, Snailfish, Tree};
 
     const INPUTS: &str = indoc! {"
         [[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
@@ -244,7 +495,7 @@ mod tests {
     #[test]
     pub fn parse() {
         let assert_parse = |input, expected_values: Vec<u16>, expected_depths: Vec<u8>| {
-            let tree = SnaifishNumber::parse(input);
+            let tree = SnaifishNumber::parse(input).unwrap();
             assert_eq!(tree.values, expected_values);
             assert_eq!(tree.depths, expected_depths);
         };
@@ -264,10 +515,93 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn display() {
+        let assert_display = |input: &str| {
+            let number = input.parse::<SnaifishNumber>().unwrap();
+            assert_eq!(number.to_string(), input);
+        };
+
+        assert_display("10");
+        assert_display("[0,0]");
+        assert_display("[[1,9],[8,5]]");
+        assert_display("[[[[1,2],[3,4]],[[5,6],[7,8]]],9]");
+        assert_display("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]");
+
+        assert!("[1]".parse::<SnaifishNumber>().is_err());
+        assert!("[1,2,3]".parse::<SnaifishNumber>().is_err());
+    }
+
+    #[test]
+    pub fn operators() {
+        let a: SnaifishNumber = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+        let b: SnaifishNumber = "[1,1]".parse().unwrap();
+        assert_eq!((a + b).to_string(), "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]");
+
+        let input = super::parse(INPUTS).unwrap();
+        let sum: SnaifishNumber = input.iter().sum::<Option<_>>().unwrap();
+        assert_eq!(
+            sum.to_string(),
+            "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
+        );
+        assert_eq!(sum.magnitude(), 4140);
+        assert_eq!(
+            Vec::<SnaifishNumber>::new().into_iter().sum::<Option<_>>(),
+            None
+        );
+        assert_eq!(super::part_1(&vec![]), 0);
+    }
+
+    #[test]
+    pub fn trace() {
+        let a: SnaifishNumber = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse().unwrap();
+        let b: SnaifishNumber = "[1,1]".parse().unwrap();
+        let steps = a.trace_add(b).collect::<Vec<_>>();
+        let trace = steps
+            .iter()
+            .map(|step| step.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            trace,
+            indoc! {"
+                after addition: [[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]
+                after explode:  [[[[0,7],4],[7,[[8,4],9]]],[1,1]]
+                after explode:  [[[[0,7],4],[15,[0,13]]],[1,1]]
+                after split:    [[[[0,7],4],[[7,8],[0,13]]],[1,1]]
+                after split:    [[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]
+                after explode:  [[[[0,7],4],[[7,8],[6,0]]],[8,1]]"}
+        );
+        assert_eq!(
+            steps.iter().map(|step| step.action).collect::<Vec<_>>(),
+            [
+                Action::Addition,
+                Action::Explode { index: 0 },
+                Action::Explode { index: 4 },
+                Action::Split { value: 15 },
+                Action::Split { value: 13 },
+                Action::Explode { index: 6 },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("[1,2]\n[[1,2],3").unwrap_err();
+        assert_eq!((err.day, err.line), (18, 2));
+        assert_eq!(err.reason, "unclosed pair");
+
+        let err = SnaifishNumber::parse("[1;2]").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.text, ";");
+
+        assert!(SnaifishNumber::parse("[1,2]]").is_err());
+    }
+
     #[test]
     pub fn parse_tree() {
         let assert_parse = |input: &str| {
-            let tree = super::Tree::parse(&mut input.chars());
+            let tree = Tree::parse(input).unwrap();
             assert_eq!(format!("{}", tree), input);
         };
 
@@ -276,27 +610,33 @@ mod tests {
         assert_parse("[10,0]");
         assert_parse("[[1,2],[3,4]]");
         assert_parse("[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]");
+
+        let err = super::parse_as::<Tree>("[1,2]\n[[1,2],3").unwrap_err();
+        assert_eq!((err.line, err.reason.as_str()), (2, "unclosed pair"));
+        let err = Tree::parse("[1;2]").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (3, ";"));
+        assert!(Tree::parse("[1,2]]").is_err());
     }
 
     #[test]
     pub fn add() {
         let assert_add = |a, b, expected| {
             {
-                let a = SnaifishNumber::parse(a);
-                let b = SnaifishNumber::parse(b);
+                let a = SnaifishNumber::parse(a).unwrap();
+                let b = SnaifishNumber::parse(b).unwrap();
                 let result = a.add(b);
-                assert_eq!(result, SnaifishNumber::parse(expected));
+                assert_eq!(result, SnaifishNumber::parse(expected).unwrap());
             }
 
-            let a = Tree::parse(&mut a.chars());
-            let b = Tree::parse(&mut b.chars());
-            let result = a.add(&b);
+            let a = Tree::parse(a).unwrap();
+            let b = Tree::parse(b).unwrap();
+            let result = a.add(b);
             assert_eq!(format!("{}", result), expected);
         };
 
         assert_add("[1,2]", "[[3,4],5]", "[[1,2],[[3,4],5]]");
         assert_add("[1,1]", "[2,2]", "[[1,1],[2,2]]");
-        assert_add("[[1,1],[2.2]]", "[3,3]", "[[[1,1],[2,2]],[3,3]]");
+        assert_add("[[1,1],[2,2]]", "[3,3]", "[[[1,1],[2,2]],[3,3]]");
         assert_add(
             "[[[1,1],[2,2]],[3,3]]",
             "[4,4]",
@@ -313,14 +653,14 @@ mod tests {
     pub fn explode() {
         let assert_explode = |input, expected| {
             {
-                let mut input = SnaifishNumber::parse(input);
+                let mut input = SnaifishNumber::parse(input).unwrap();
                 input.explode();
-                assert_eq!(input, SnaifishNumber::parse(expected));
+                assert_eq!(input, SnaifishNumber::parse(expected).unwrap());
             }
             {
-                let mut tree = Tree::parse(&mut input.chars());
-                println!("{}", tree);
-                assert!(tree.explode(0));
+                let mut tree = Tree::parse(input).unwrap();
+                assert!(tree.explode());
+                assert_eq!(format!("{}", tree), expected);
             }
         };
 
@@ -340,9 +680,14 @@ mod tests {
     #[test]
     pub fn split() {
         let assert_split = |input, expected| {
-            let mut input = SnaifishNumber::parse(input);
-            input.split();
-            assert_eq!(input, SnaifishNumber::parse(expected));
+            {
+                let mut input = SnaifishNumber::parse(input).unwrap();
+                input.split();
+                assert_eq!(input, SnaifishNumber::parse(expected).unwrap());
+            }
+            let mut tree = Tree::parse(input).unwrap();
+            assert!(tree.split());
+            assert_eq!(format!("{}", tree), expected);
         };
         assert_split("[10,0]", "[[5,5],0]");
         assert_split("11", "[5,6]");
@@ -360,13 +705,22 @@ mod tests {
     #[test]
     pub fn addition() {
         let assert_add = |input, expected| {
-            let input = super::parse(input);
+            {
+                let input = super::parse(input).unwrap();
+                let mut result = input.first().unwrap().clone();
+                for tree in input.iter().skip(1) {
+                    result = result.add(tree.clone());
+                    result.reduce();
+                }
+                assert_eq!(result, SnaifishNumber::parse(expected).unwrap());
+            }
+            let input = super::parse_as::<Tree>(input).unwrap();
             let mut result = input.first().unwrap().clone();
             for tree in input.iter().skip(1) {
                 result = result.add(tree.clone());
                 result.reduce();
             }
-            assert_eq!(result, SnaifishNumber::parse(expected));
+            assert_eq!(format!("{}", result), expected);
         };
 
         assert_add(
@@ -444,8 +798,8 @@ mod tests {
     #[test]
     pub fn magnitude() {
         let assert_magnitude = |input, expected| {
-            let input = SnaifishNumber::parse(input);
-            assert_eq!(input.magnitude(), expected);
+            assert_eq!(SnaifishNumber::parse(input).unwrap().magnitude(), expected);
+            assert_eq!(Tree::parse(input).unwrap().magnitude(), expected);
         };
         assert_magnitude("[[1,2],[[3,4],5]]", 143);
         assert_magnitude("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", 1384);
@@ -459,15 +813,23 @@ mod tests {
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 4140);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 3993);
     }
+
+    #[test]
+    pub fn cross_check() {
+        let numbers = super::parse_as::<SnaifishNumber>(INPUTS).unwrap();
+        let trees = super::parse_as::<Tree>(INPUTS).unwrap();
+        assert_eq!(super::solve_part_1(&numbers), super::solve_part_1(&trees));
+        assert_eq!(super::solve_part_2(&numbers), super::solve_part_2(&trees));
+    }
 }
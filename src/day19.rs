@@ -1,61 +1,212 @@
+use std::cmp::Ordering;
+
 use glam::IVec3;
 use hashbrown::{HashMap, HashSet};
 
-type Data = (Vec<IVec3>, HashSet<IVec3>);
+use crate::error::{parse_number, ParseError};
+
+const DAY: u8 = 19;
+
+pub type Data = Alignment;
+
+/// The beacons seen by a scanner, relative to it
+pub type Scanner = HashSet<IVec3>;
 
-// The description mentions needing 12, but 3 is enough
-// to reconstruct the grid properly for the examples and the given input
-const MIN_MATCHING_BEACONS: usize = 3;
+/// The number of beacons two overlapping scanners have in common according to the puzzle
+pub const PUZZLE_OVERLAP: usize = 12;
 
 lazy_static::lazy_static! {
-    static ref ALL_ROTATIONS: Vec<IVec3> = vec![
-        IVec3::new(0, 0, 0),
-        IVec3::new(0, 0, 1),
-        IVec3::new(0, 0, 2),
-        IVec3::new(0, 0, 3),
-        IVec3::new(0, 1, 0),
-        IVec3::new(0, 1, 1),
-        IVec3::new(0, 1, 2),
-        IVec3::new(0, 1, 3),
-        IVec3::new(0, 2, 0),
-        IVec3::new(0, 2, 1),
-        IVec3::new(0, 2, 2),
-        IVec3::new(0, 2, 3),
-        IVec3::new(0, 3, 0),
-        IVec3::new(0, 3, 1),
-        IVec3::new(0, 3, 2),
-        IVec3::new(0, 3, 3),
-        IVec3::new(1, 0, 0),
-        IVec3::new(1, 0, 1),
-        IVec3::new(1, 0, 2),
-        IVec3::new(1, 0, 3),
-        IVec3::new(1, 2, 0),
-        IVec3::new(1, 2, 1),
-        IVec3::new(1, 2, 2),
-        IVec3::new(1, 2, 3)
-    ];
-    // computed by doing:
-    // static ref ALL_ROTATIONS: Vec<IVec3> = {
-    //     let mut axes_rotated_set = HashSet::new();
-    //     let mut rotations = vec![];
-    //     for x in 0..=3 {
-    //         for y in 0..=3 {
-    //             for z in 0..=3 {
-    //                 let rot = IVec3::new(x, y, z);
-    //                 let axes_rotated = [
-    //                     rotate(IVec3::new(1, 0, 0),&rot),
-    //                     rotate(IVec3::new(0, 1, 0), &rot),
-    //                     rotate(IVec3::new(0, 0, 1), &rot),
-    //                 ];
-    //                 if !axes_rotated_set.contains(&axes_rotated) {
-    //                     axes_rotated_set.insert(axes_rotated);
-    //                     rotations.push(rot);
-    //                 }
-    //             }
-    //         }
-    //     }
-    //     rotations
-    // };
+    static ref ALL_ROTATIONS: Vec<Rotation> = {
+        // Different quarter turns around the axes can end up facing the same way
+        let mut rotations = vec![];
+        for x in 0..=3 {
+            for y in 0..=3 {
+                for z in 0..=3 {
+                    let rotation = Rotation::from_quarter_turns(IVec3::new(x, y, z));
+                    if !rotations.contains(&rotation) {
+                        rotations.push(rotation);
+                    }
+                }
+            }
+        }
+        rotations
+    };
+}
+
+/// A rotation matrix, the columns are where the x, y and z axes end up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    pub x_axis: IVec3,
+    pub y_axis: IVec3,
+    pub z_axis: IVec3,
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        x_axis: IVec3::X,
+        y_axis: IVec3::Y,
+        z_axis: IVec3::Z,
+    };
+
+    /// The 24 ways a scanner can be facing
+    pub fn all() -> &'static [Rotation] {
+        &ALL_ROTATIONS
+    }
+
+    /// Rotates by a quarter turn around each axis the given number of times, x first
+    fn from_quarter_turns(turns: IVec3) -> Self {
+        Self {
+            x_axis: rotate(IVec3::X, &turns),
+            y_axis: rotate(IVec3::Y, &turns),
+            z_axis: rotate(IVec3::Z, &turns),
+        }
+    }
+
+    pub fn apply(&self, v: IVec3) -> IVec3 {
+        self.x_axis * v.x + self.y_axis * v.y + self.z_axis * v.z
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlignmentOptions {
+    /// The number of beacons a scanner needs in common with the known beacons to be aligned.
+    /// The puzzle says 12 but 3 is enough to align the examples and the inputs, and it's faster
+    pub min_overlap: usize,
+    /// Only accepts the alignments where the scanner has [`PUZZLE_OVERLAP`] beacons in common
+    /// with the known beacons, so a low `min_overlap` can't produce a wrong alignment
+    pub verify: bool,
+    /// Only tries the rotations of a scanner once its [`Fingerprint`] shows it can overlap
+    /// an aligned scanner
+    pub prefilter: bool,
+}
+
+impl Default for AlignmentOptions {
+    fn default() -> Self {
+        Self {
+            min_overlap: 3,
+            verify: false,
+            prefilter: true,
+        }
+    }
+}
+
+/// The squared distances between every pair of beacons of a scanner, sorted.
+/// They don't depend on where the scanner is or which way it faces, so two scanners
+/// sharing n beacons share at least n * (n - 1) / 2 distances
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fingerprint(Vec<i32>);
+
+impl Fingerprint {
+    pub fn new(beacons: &HashSet<IVec3>) -> Self {
+        let beacons = beacons.iter().collect::<Vec<_>>();
+        let mut distances = vec![];
+        for (i, a) in beacons.iter().enumerate() {
+            for b in &beacons[i + 1..] {
+                let d = **a - **b;
+                distances.push(d.dot(d));
+            }
+        }
+        distances.sort_unstable();
+        Self(distances)
+    }
+
+    /// The number of distances found in both fingerprints, counting the duplicates
+    pub fn common(&self, other: &Fingerprint) -> usize {
+        let (mut i, mut j, mut count) = (0, 0, 0);
+        while i < self.0.len() && j < other.0.len() {
+            match self.0[i].cmp(&other.0[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    count += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Whether the scanners can have `overlap` beacons in common
+    pub fn may_overlap(&self, other: &Fingerprint, overlap: usize) -> bool {
+        self.common(other) >= overlap * overlap.saturating_sub(1) / 2
+    }
+}
+
+/// How much work the alignment did and how much the fingerprints saved
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AlignmentStats {
+    /// The pairs of scanners whose fingerprints were compared
+    pub fingerprint_checks: usize,
+    /// The times a scanner was skipped because it can't overlap any aligned scanner
+    pub pruned: usize,
+    /// The times the rotations of a scanner were tried
+    pub attempts: usize,
+    pub rotations_tried: usize,
+}
+
+impl std::fmt::Display for AlignmentStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} attempts trying {} rotations, {} pruned after {} fingerprint checks",
+            self.attempts, self.rotations_tried, self.pruned, self.fingerprint_checks
+        )
+    }
+}
+
+/// Where a scanner is and which way it faces relative to scanner 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScannerAlignment {
+    pub rotation: Rotation,
+    /// The position of the scanner
+    pub translation: IVec3,
+}
+
+impl ScannerAlignment {
+    pub const ORIGIN: ScannerAlignment = ScannerAlignment {
+        rotation: Rotation::IDENTITY,
+        translation: IVec3::ZERO,
+    };
+
+    /// Converts a beacon seen by the scanner to the coordinates of scanner 0
+    pub fn apply(&self, beacon: IVec3) -> IVec3 {
+        self.rotation.apply(beacon) + self.translation
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alignment {
+    /// The alignment of each scanner in the order of the input,
+    /// `None` if the scanner doesn't overlap any aligned scanner
+    pub scanners: Vec<Option<ScannerAlignment>>,
+    /// The beacons of the aligned scanners in the coordinates of scanner 0
+    pub beacons: HashSet<IVec3>,
+    pub stats: AlignmentStats,
+}
+
+impl Alignment {
+    /// The index and alignment of each aligned scanner
+    pub fn aligned(&self) -> impl Iterator<Item = (usize, &ScannerAlignment)> {
+        self.scanners
+            .iter()
+            .enumerate()
+            .filter_map(|(scanner_id, scanner)| scanner.as_ref().map(|s| (scanner_id, s)))
+    }
+
+    /// The index of each scanner that couldn't be aligned
+    pub fn unaligned(&self) -> Vec<usize> {
+        self.scanners
+            .iter()
+            .enumerate()
+            .filter(|(_, scanner)| scanner.is_none())
+            .map(|(scanner_id, _)| scanner_id)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.scanners.iter().all(Option::is_some)
+    }
 }
 
 // Rotate a beacon around the given axis
@@ -80,8 +231,8 @@ fn rotate(beacon: IVec3, axis_rotations: &IVec3) -> IVec3 {
 }
 
 /// Rotates all beacons in the scanner
-fn rotate_scanner(scanner: &HashSet<IVec3>, rot: &IVec3) -> HashSet<IVec3> {
-    scanner.iter().map(|p| rotate(*p, rot)).collect()
+fn rotate_scanner(scanner: &HashSet<IVec3>, rotation: &Rotation) -> HashSet<IVec3> {
+    scanner.iter().map(|p| rotation.apply(*p)).collect()
 }
 
 /// Computes the distances between a given beacon and all other beacons in the scanner
@@ -98,92 +249,195 @@ fn scanner_distances(scanner: &HashSet<IVec3>) -> HashMap<IVec3, HashSet<IVec3>>
     map
 }
 
-fn find_match(
-    rotated_distances: &HashMap<IVec3, HashSet<IVec3>>,
-    beacons_distances: &HashMap<IVec3, HashSet<IVec3>>,
+/// The translations lining up at least `min_match_size` beacons of the rotated scanner
+/// with the known beacons
+fn find_matches<'a>(
+    rotated_distances: &'a HashMap<IVec3, HashSet<IVec3>>,
+    beacons_distances: &'a HashMap<IVec3, HashSet<IVec3>>,
     min_match_size: usize,
-) -> Option<IVec3> {
-    for (rotated, rotated_dist) in rotated_distances {
-        for (beacon, beacon_dist) in beacons_distances {
-            if rotated_dist.intersection(beacon_dist).count() >= min_match_size {
-                return Some(*rotated - *beacon);
-            }
-        }
-    }
-    None
+) -> impl Iterator<Item = IVec3> + 'a {
+    rotated_distances
+        .iter()
+        .flat_map(move |(rotated, rotated_dist)| {
+            beacons_distances
+                .iter()
+                .filter(move |(_, beacon_dist)| {
+                    rotated_dist.intersection(beacon_dist).count() >= min_match_size
+                })
+                .map(move |(beacon, _)| *beacon - *rotated)
+        })
 }
 
-fn find_beacons(scanners: &[HashSet<IVec3>]) -> (Vec<IVec3>, HashSet<IVec3>) {
-    let mut scanner_positions = vec![IVec3::ZERO];
+/// Finds the position and rotation of every scanner relative to the first one.
+/// Stops when a pass over the unaligned scanners can't align any of them
+pub fn align(scanners: &[Scanner], options: &AlignmentOptions) -> Alignment {
+    let mut alignments = vec![None; scanners.len()];
     let mut beacons = HashSet::new();
-    beacons.extend(scanners[0].iter());
+    if let Some(first) = scanners.first() {
+        alignments[0] = Some(ScannerAlignment::ORIGIN);
+        beacons.extend(first.iter());
+    }
     // Cache the distances between each beacons
     let mut beacons_distances = scanner_distances(&beacons);
 
     // Keep a map of all the unchecked scanners
     // We remove a scanner when an offset and rotation is found
     let mut unchecked_scanners = HashMap::new();
-    for (scanner_id, scanner) in scanners.iter().skip(1).enumerate() {
+    for (scanner_id, scanner) in scanners.iter().enumerate().skip(1) {
         // Cache the rotation and the beacon distances of each scanner
-        for rot in ALL_ROTATIONS.iter() {
-            let rotated_scanner = rotate_scanner(scanner, rot);
+        for rotation in Rotation::all() {
+            let rotated_scanner = rotate_scanner(scanner, rotation);
             let rotated_distances = scanner_distances(&rotated_scanner);
             unchecked_scanners
                 .entry(scanner_id)
                 .or_insert(vec![])
-                .push((rotated_scanner, rotated_distances));
+                .push((*rotation, rotated_scanner, rotated_distances));
         }
     }
 
+    let mut stats = AlignmentStats::default();
+    let fingerprints = match options.prefilter {
+        true => scanners.iter().map(Fingerprint::new).collect(),
+        false => vec![],
+    };
+    // The unchecked scanners that can overlap an aligned scanner
+    let mut promising = HashSet::new();
+    if options.prefilter && !scanners.is_empty() {
+        for scanner_id in unchecked_scanners.keys() {
+            stats.fingerprint_checks += 1;
+            if fingerprints[*scanner_id].may_overlap(&fingerprints[0], options.min_overlap) {
+                promising.insert(*scanner_id);
+            }
+        }
+    }
+
+    // The fingerprints only compare pairs of scanners but the beacons a scanner shares with
+    // the known ones can come from several scanners, so a pass without progress is done
+    // again without pruning
+    let mut prune = options.prefilter;
     while !unchecked_scanners.is_empty() {
-        for (scanner_id, scanner) in unchecked_scanners.clone() {
-            for (rotated_scanner, rotated_distances) in scanner {
-                if let Some(offset) =
-                    find_match(&rotated_distances, &beacons_distances, MIN_MATCHING_BEACONS)
-                {
-                    for beacon in rotated_scanner {
-                        let beacon = beacon - offset;
-                        beacons.insert(beacon);
-                        beacons_distances.insert(beacon, beacon_distances(beacon, &beacons));
+        let mut progress = false;
+        let mut scanner_ids = unchecked_scanners.keys().copied().collect::<Vec<_>>();
+        scanner_ids.sort_unstable();
+        for scanner_id in scanner_ids {
+            if prune && !promising.contains(&scanner_id) {
+                stats.pruned += 1;
+                continue;
+            }
+            stats.attempts += 1;
+            let found = unchecked_scanners[&scanner_id].iter().enumerate().find_map(
+                |(i, (_, rotated_scanner, rotated_distances))| {
+                    stats.rotations_tried += 1;
+                    let verified = |translation: &IVec3| {
+                        !options.verify
+                            || rotated_scanner
+                                .iter()
+                                .filter(|beacon| beacons.contains(&(**beacon + *translation)))
+                                .count()
+                                >= PUZZLE_OVERLAP
+                    };
+                    find_matches(rotated_distances, &beacons_distances, options.min_overlap)
+                        .find(verified)
+                        .map(|translation| (i, translation))
+                },
+            );
+            if let Some((i, translation)) = found {
+                let mut rotations = unchecked_scanners.remove(&scanner_id).unwrap();
+                let (rotation, rotated_scanner, _) = rotations.swap_remove(i);
+                for beacon in rotated_scanner {
+                    let beacon = beacon + translation;
+                    beacons.insert(beacon);
+                    beacons_distances.insert(beacon, beacon_distances(beacon, &beacons));
+                }
+                alignments[scanner_id] = Some(ScannerAlignment {
+                    rotation,
+                    translation,
+                });
+                progress = true;
+                if options.prefilter {
+                    for other_id in unchecked_scanners.keys() {
+                        stats.fingerprint_checks += 1;
+                        if fingerprints[*other_id]
+                            .may_overlap(&fingerprints[scanner_id], options.min_overlap)
+                        {
+                            promising.insert(*other_id);
+                        }
                     }
-                    scanner_positions.push(offset);
-                    unchecked_scanners.remove(&scanner_id);
-                    break;
                 }
             }
         }
+        if !progress && !prune {
+            // The remaining scanners don't overlap the aligned ones
+            break;
+        }
+        prune = options.prefilter && progress;
+    }
+    Alignment {
+        scanners: alignments,
+        beacons,
+        stats,
     }
-    (scanner_positions, beacons)
 }
 
-pub fn parse(input: &str) -> Data {
-    let scanners = input
+/// Parses the beacons seen by each scanner, without aligning them
+pub fn parse_scanners(input: &str) -> Result<Vec<Scanner>, ParseError> {
+    input
         .split("\n\n")
         .map(|scanner| {
             scanner
                 .lines()
                 .skip(1)
-                .map(|l| l.split(',').flat_map(|val| val.parse()).collect())
-                .map(|v: Vec<i32>| IVec3::new(v[0], v[1], v[2]))
+                .map(|l| {
+                    let v = l
+                        .split(',')
+                        .map(|val| parse_number(DAY, input, val))
+                        .collect::<Result<Vec<i32>, _>>()?;
+                    match v[..] {
+                        [x, y, z] => Ok(IVec3::new(x, y, z)),
+                        _ => Err(ParseError::new(DAY, input, l, "expected `x,y,z`")),
+                    }
+                })
                 .collect()
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let scanners = parse_scanners(input)?;
     // Part 1 and 2 need the result of this computation so it's faster to
     // do it directly after parsing
-    find_beacons(&scanners)
+    let alignment = align(&scanners, &AlignmentOptions::default());
+    match alignment.unaligned().first() {
+        Some(scanner_id) => {
+            let header = input
+                .split("\n\n")
+                .nth(*scanner_id)
+                .and_then(|scanner| scanner.lines().next())
+                .unwrap_or_default();
+            Err(ParseError::new(
+                DAY,
+                input,
+                header,
+                "the scanner doesn't overlap any aligned scanner",
+            ))
+        }
+        None => Ok(alignment),
+    }
 }
 
 pub fn part_1(data: &Data) -> usize {
-    let (_, beacons) = data;
-    beacons.len()
+    data.beacons.len()
 }
 
 pub fn part_2(data: &Data) -> usize {
-    let (scanner_positions, _) = data;
+    let scanner_positions = data
+        .aligned()
+        .map(|(_, scanner)| scanner.translation)
+        .collect::<Vec<_>>();
     let mut max_dist = 0;
-    for pos_1 in scanner_positions {
-        for pos_2 in scanner_positions.clone() {
-            max_dist = max_dist.max((*pos_1 - pos_2).abs().to_array().iter().sum());
+    for pos_1 in &scanner_positions {
+        for pos_2 in &scanner_positions {
+            max_dist = max_dist.max((*pos_1 - *pos_2).abs().to_array().iter().sum());
         }
     }
     max_dist as usize
@@ -334,21 +588,153 @@ mod tests {
 
     #[test]
     pub fn all_rotations() {
-        println!("{:?}", super::ALL_ROTATIONS.iter().collect::<Vec<_>>());
+        use glam::IVec3;
+
+        use super::Rotation;
+
+        assert_eq!(Rotation::all().len(), 24);
+        assert_eq!(Rotation::all()[0], Rotation::IDENTITY);
+        for rotation in Rotation::all() {
+            // rotations keep the axes perpendicular and don't mirror
+            let Rotation {
+                x_axis,
+                y_axis,
+                z_axis,
+            } = *rotation;
+            assert_eq!(x_axis.cross(y_axis), z_axis);
+            assert_eq!(
+                rotation
+                    .apply(IVec3::new(1, 2, 3))
+                    .abs()
+                    .to_array()
+                    .iter()
+                    .sum::<i32>(),
+                6
+            );
+        }
+    }
+
+    #[test]
+    pub fn align() {
+        use glam::IVec3;
+
+        use super::{align, parse_scanners, AlignmentOptions, PUZZLE_OVERLAP};
+
+        let input = super::parse(INPUTS).unwrap();
+        let scanners = input
+            .aligned()
+            .map(|(_, scanner)| scanner.translation)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scanners,
+            [
+                IVec3::new(0, 0, 0),
+                IVec3::new(68, -1246, -43),
+                IVec3::new(1105, -1205, 1229),
+                IVec3::new(-92, -2380, -20),
+                IVec3::new(-20, -1133, 1061),
+            ]
+        );
+
+        // Every scanner is aligned the same way with the puzzle's rule
+        let scanners = parse_scanners(INPUTS).unwrap();
+        let options = AlignmentOptions {
+            min_overlap: PUZZLE_OVERLAP,
+            verify: true,
+            prefilter: true,
+        };
+        let alignment = align(&scanners, &options);
+        assert_eq!(alignment.scanners, input.scanners);
+        assert_eq!(alignment.beacons, input.beacons);
+        let scanner_1 = alignment.scanners[1].unwrap();
+        let overlap = scanners[1]
+            .iter()
+            .filter(|beacon| scanners[0].contains(&scanner_1.apply(**beacon)))
+            .count();
+        assert_eq!(overlap, 12);
+    }
+
+    #[test]
+    pub fn fingerprint() {
+        use super::{align, parse_scanners, AlignmentOptions, Fingerprint, PUZZLE_OVERLAP};
+
+        let scanners = parse_scanners(INPUTS).unwrap();
+        let fingerprints = scanners.iter().map(Fingerprint::new).collect::<Vec<_>>();
+        // Scanner 0 only overlaps scanner 1
+        assert!(fingerprints[0].may_overlap(&fingerprints[1], 12));
+        for other in &fingerprints[2..] {
+            assert!(!fingerprints[0].may_overlap(other, 12));
+        }
+
+        // Each scanner is only tried once it can be aligned
+        let options = AlignmentOptions {
+            min_overlap: PUZZLE_OVERLAP,
+            ..AlignmentOptions::default()
+        };
+        let pruned = align(&scanners, &options);
+        assert_eq!((pruned.stats.attempts, pruned.stats.pruned), (4, 1));
+
+        let options = AlignmentOptions {
+            prefilter: false,
+            ..options
+        };
+        let unpruned = align(&scanners, &options);
+        assert_eq!(unpruned.stats.fingerprint_checks, 0);
+        assert!(unpruned.stats.rotations_tried > pruned.stats.rotations_tried);
+        assert_eq!(pruned.scanners, unpruned.scanners);
+    }
+
+    #[test]
+    pub fn unaligned() {
+        use super::{align, parse_scanners, AlignmentOptions};
+
+        // The beacons of scanner 0 spread out 3 times further don't overlap anything
+        let far = INPUTS
+            .split("\n\n")
+            .next()
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|l| {
+                let v = l.split(',').map(|v| v.parse::<i32>().unwrap() * 3);
+                v.map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+            });
+        let input = format!(
+            "{}\n--- scanner 5 ---\n{}\n",
+            INPUTS,
+            far.collect::<Vec<_>>().join("\n")
+        );
+
+        let scanners = parse_scanners(&input).unwrap();
+        for prefilter in [true, false] {
+            let options = AlignmentOptions {
+                prefilter,
+                ..AlignmentOptions::default()
+            };
+            let alignment = align(&scanners, &options);
+            assert!(!alignment.is_complete());
+            assert_eq!(alignment.unaligned(), [5]);
+            assert_eq!(alignment.aligned().count(), 5);
+            assert_eq!(alignment.beacons.len(), 79);
+        }
 
-        assert_eq!(super::ALL_ROTATIONS.len(), 24);
+        let error = super::parse(&input).unwrap_err();
+        assert_eq!(
+            (error.line, error.text.as_str()),
+            (138, "--- scanner 5 ---")
+        );
     }
 
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 79);
     }
 
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
         assert_eq!(result, 3621);
     }
@@ -1,10 +1,15 @@
-use std::{iter::Cycle, ops::RangeInclusive};
-
 use hashbrown::HashMap;
+use num_bigint::BigUint;
 use serde_scan::scan;
 
-type Data = (u16, u16);
-type Die = Cycle<RangeInclusive<u16>>;
+use crate::{error::ParseError, grid::Grid};
+
+const DAY: u8 = 21;
+
+pub type Data = (u16, u16);
+
+/// Size of the board of the puzzle
+const BOARD_SIZE: u16 = 10;
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct Player {
@@ -17,97 +22,602 @@ impl Player {
         Self { position, score: 0 }
     }
 
-    fn advance_mut(&mut self, amount: u16) {
-        self.position = (self.position + amount - 1) % 10 + 1;
+    fn advance_mut(&mut self, amount: u16, board_size: u16) {
+        self.position = (self.position + amount - 1) % board_size + 1;
         self.score += self.position;
     }
 
-    fn advance(&self, amount: u16) -> Self {
+    fn advance(&self, amount: u16, board_size: u16) -> Self {
         let mut new_player = *self;
-        new_player.advance_mut(amount);
+        new_player.advance_mut(amount, board_size);
         new_player
     }
 }
 
-fn roll(die: &mut Die, roll_count: &mut usize) -> u16 {
-    let mut roll_sum = 0;
-    for _ in 0..3 {
-        roll_sum += die.next().expect("Cycle should be infinite");
+/// The rules of a game played with the Dirac die
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// The spaces are numbered from 1 to `board_size`
+    pub board_size: u16,
+    pub die_faces: u16,
+    pub rolls_per_turn: u16,
+    /// The score a player needs to win
+    pub target: u16,
+}
+
+impl Rules {
+    /// The practice game of part 1, played with a deterministic die
+    pub const PRACTICE: Rules = Rules {
+        board_size: BOARD_SIZE,
+        die_faces: 100,
+        rolls_per_turn: 3,
+        target: 1000,
+    };
+
+    /// The game of part 2, played with the Dirac die
+    pub const PUZZLE: Rules = Rules {
+        board_size: BOARD_SIZE,
+        die_faces: 3,
+        rolls_per_turn: 3,
+        target: 21,
+    };
+
+    /// Every sum of the rolls of a turn with the number of universes producing it
+    pub fn roll_distribution(&self) -> Vec<(u16, usize)> {
+        let mut distribution = vec![(0, 1)];
+        for _ in 0..self.rolls_per_turn {
+            let mut sums = HashMap::new();
+            for (sum, count) in distribution {
+                for face in 1..=self.die_faces {
+                    *sums.entry(sum + face).or_insert(0) += count;
+                }
+            }
+            distribution = sums.into_iter().collect();
+        }
+        distribution.sort_unstable();
+        distribution
     }
-    *roll_count += 3;
-    roll_sum
 }
 
-const POSSIBLE_ROLLS: [(u16, usize); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
-fn count_wins(
-    cache: &mut HashMap<(Player, Player), (usize, usize)>,
-    player_a: Player,
-    player_b: Player,
-) -> (usize, usize) {
-    let key = (player_a, player_b);
+/// Rolls 1, 2, 3 up to the number of faces and starts over
+pub fn deterministic_die(faces: u16) -> impl Iterator<Item = u16> {
+    (1..=faces).cycle()
+}
 
-    if let Some(value) = cache.get(&key) {
-        return *value;
+/// A die rolling pseudo random numbers, the same seed always gives the same rolls
+#[derive(Debug, Clone)]
+pub struct SeededDie {
+    faces: u16,
+    state: u64,
+}
+
+impl SeededDie {
+    pub fn new(faces: u16, seed: u64) -> Self {
+        assert!(faces > 0, "the die needs at least one face");
+        Self { faces, state: seed }
     }
+}
 
-    let mut player_a_wins = 0;
-    let mut player_b_wins = 0;
-    for (value, count) in POSSIBLE_ROLLS {
-        let new_player_a = player_a.advance(value);
-        if new_player_a.score >= 21 {
-            player_a_wins += count;
-        } else {
-            let (player_b_win, player_a_win) = count_wins(cache, player_b, new_player_a);
-            player_a_wins += player_a_win * count;
-            player_b_wins += player_b_win * count;
+impl Iterator for SeededDie {
+    type Item = u16;
+
+    /// splitmix64, good enough for a board game
+    fn next(&mut self) -> Option<u16> {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Some((z % self.faces as u64) as u16 + 1)
+    }
+}
+
+/// A turn of a [`Game`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    /// Index of the player in the starting positions
+    pub player: usize,
+    pub rolls: Vec<u16>,
+    pub position: u16,
+    pub score: u16,
+}
+
+/// Formats the turn like the puzzle text does
+impl std::fmt::Display for Turn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rolls = self
+            .rolls
+            .iter()
+            .map(|roll| roll.to_string())
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "Player {} rolls {} and moves to space {} for a total score of {}.",
+            self.player + 1,
+            rolls.join("+"),
+            self.position,
+            self.score
+        )
+    }
+}
+
+/// Plays a game with a single outcome, yielding every turn until a player wins.
+/// Any iterator can be used as the die, a scripted die can simply be a `Vec` of the rolls,
+/// the game stops early if the die runs out in the middle of a turn
+pub struct Game<D> {
+    rules: Rules,
+    die: D,
+    players: Vec<Player>,
+    current: usize,
+    roll_count: usize,
+    winner: Option<usize>,
+}
+
+impl<D: Iterator<Item = u16>> Game<D> {
+    /// `rules.die_faces` isn't used, the faces are up to the die.
+    /// Panics if a starting position isn't on the board, [`parse`] already checks the input
+    pub fn new(rules: Rules, positions: &[u16], die: impl IntoIterator<IntoIter = D>) -> Self {
+        assert!(
+            positions
+                .iter()
+                .all(|position| (1..=rules.board_size).contains(position)),
+            "the starting positions must be on the board"
+        );
+        Self {
+            rules,
+            die: die.into_iter(),
+            players: positions
+                .iter()
+                .map(|position| Player::new(*position))
+                .collect(),
+            current: 0,
+            roll_count: 0,
+            winner: None,
         }
     }
 
-    let result = (player_a_wins, player_b_wins);
-    cache.insert(key, result);
-    result
+    /// The number of times the die was rolled
+    pub fn roll_count(&self) -> usize {
+        self.roll_count
+    }
+
+    pub fn scores(&self) -> Vec<u16> {
+        self.players.iter().map(|player| player.score).collect()
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.winner
+    }
 }
 
-pub fn parse(input: &str) -> Data {
-    let players = input
-        .lines()
-        .flat_map(|l| scan!("Player {} starting position: {}" <- l))
-        .collect::<Vec<(u16, u16)>>();
+impl<D: Iterator<Item = u16>> Iterator for Game<D> {
+    type Item = Turn;
 
-    (players[0].1, players[1].1)
+    fn next(&mut self) -> Option<Turn> {
+        if self.winner.is_some() || self.players.is_empty() {
+            return None;
+        }
+        let rolls = self
+            .die
+            .by_ref()
+            .take(self.rules.rolls_per_turn as usize)
+            .collect::<Vec<_>>();
+        self.roll_count += rolls.len();
+        if rolls.len() < self.rules.rolls_per_turn as usize {
+            return None;
+        }
+
+        let player = &mut self.players[self.current];
+        player.advance_mut(rolls.iter().sum(), self.rules.board_size);
+        let turn = Turn {
+            player: self.current,
+            rolls,
+            position: player.position,
+            score: player.score,
+        };
+        if player.score >= self.rules.target {
+            self.winner = Some(self.current);
+        }
+        self.current = (self.current + 1) % self.players.len();
+        Some(turn)
+    }
 }
 
-pub fn part_1(input: &Data) -> usize {
-    let (player_1_pos, player_2_pos) = input;
-    let mut player_1 = Player::new(*player_1_pos);
-    let mut player_2 = Player::new(*player_2_pos);
+/// How a game starting from some positions ends across every universe
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    /// The number of universes won by each player, which grows quickly with the number of players
+    pub wins: Vec<BigUint>,
+    /// The probability of each player winning, every face of the die being equally likely.
+    /// Short games happen in fewer universes so this isn't the share of `wins`
+    pub win_probabilities: Vec<f64>,
+    /// The expected number of turns until someone wins, a turn being the rolls of a single player
+    pub expected_turns: f64,
+}
+
+impl Outcome {
+    fn empty(players: usize) -> Self {
+        Self {
+            wins: vec![BigUint::default(); players],
+            win_probabilities: vec![0.0; players],
+            expected_turns: 0.0,
+        }
+    }
+}
 
-    let mut die = (1..=100).cycle();
-    let mut roll_count = 0;
-    let max_score = 1000;
+/// Computes the outcome of games with the Dirac die,
+/// the states are memoized so reusing the engine for other starting positions is cheap
+pub struct DiracDice {
+    rules: Rules,
+    rolls: Vec<(u16, usize)>,
+    /// The number of universes created by a turn
+    universes_per_turn: usize,
+    cache: HashMap<Vec<Player>, Outcome>,
+}
 
-    loop {
-        player_1.advance_mut(roll(&mut die, &mut roll_count));
-        if player_1.score >= max_score {
-            return player_2.score as usize * roll_count;
+impl DiracDice {
+    pub fn new(rules: Rules) -> Self {
+        assert!(rules.board_size > 0, "the board needs at least one space");
+        assert!(rules.die_faces > 0, "the die needs at least one face");
+        let rolls = rules.roll_distribution();
+        Self {
+            rules,
+            universes_per_turn: rolls.iter().map(|(_, count)| count).sum(),
+            rolls,
+            cache: HashMap::new(),
         }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
 
-        player_2.advance_mut(roll(&mut die, &mut roll_count));
-        if player_2.score >= max_score {
-            return player_1.score as usize * roll_count;
+    /// The number of universes won by each player, in the order of the starting positions
+    pub fn wins(&mut self, positions: &[u16]) -> Vec<BigUint> {
+        self.outcome(positions).wins
+    }
+
+    /// The outcome of a game where the players start at `positions` and play in that order.
+    /// Panics if a starting position isn't on the board
+    pub fn outcome(&mut self, positions: &[u16]) -> Outcome {
+        assert!(
+            positions
+                .iter()
+                .all(|position| (1..=self.rules.board_size).contains(position)),
+            "the starting positions must be on the board"
+        );
+        if positions.is_empty() {
+            return Outcome::empty(0);
         }
+        let players: Vec<_> = positions
+            .iter()
+            .map(|position| Player::new(*position))
+            .collect();
+        self.count_wins(&players);
+        self.cache[&players].clone()
+    }
+
+    /// The outcome of two player games for every pair of starting positions,
+    /// the first player's position is the row and the second player's is the column
+    pub fn outcome_table(&mut self) -> Grid<Outcome> {
+        let positions = 1..=self.rules.board_size;
+        let rows = positions
+            .clone()
+            .map(|player_1| {
+                positions
+                    .clone()
+                    .map(|player_2| self.outcome(&[player_1, player_2]))
+                    .collect()
+            })
+            .collect();
+        Grid::from_rows(rows).expect("every row has a column per position")
+    }
+
+    /// Fills the cache with the outcome of `players`, the first player is the one about to play
+    fn count_wins(&mut self, players: &[Player]) {
+        if self.cache.contains_key(players) {
+            return;
+        }
+
+        let mut outcome = Outcome::empty(players.len());
+        outcome.expected_turns = 1.0;
+        for i in 0..self.rolls.len() {
+            let (value, count) = self.rolls[i];
+            let probability = count as f64 / self.universes_per_turn as f64;
+            let player = players[0].advance(value, self.rules.board_size);
+            if player.score >= self.rules.target {
+                outcome.wins[0] += count;
+                outcome.win_probabilities[0] += probability;
+            } else {
+                // the others take their turn before this player plays again
+                let mut next = players[1..].to_vec();
+                next.push(player);
+                self.count_wins(&next);
+                let next = &self.cache[&next];
+                outcome.expected_turns += probability * next.expected_turns;
+                // the current player is last in the next state
+                let n = players.len();
+                for (j, (wins, win_probability)) in
+                    next.wins.iter().zip(&next.win_probabilities).enumerate()
+                {
+                    let player = (j + 1) % n;
+                    outcome.wins[player] += wins * count;
+                    outcome.win_probabilities[player] += win_probability * probability;
+                }
+            }
+        }
+
+        self.cache.insert(players.to_vec(), outcome);
     }
 }
 
-pub fn part_2(input: &Data) -> usize {
-    let (player_1_pos, player_2_pos) = input;
-    let mut cache = HashMap::new();
-    let (player_1_wins, player_2_wins) = count_wins(
-        &mut cache,
-        Player::new(*player_1_pos),
-        Player::new(*player_2_pos),
+/// Writes a row per pair of starting positions with the outcome of the game
+pub fn outcome_table_csv(table: &Grid<Outcome>) -> String {
+    let mut csv = String::from(
+        "player_1,player_2,player_1_wins,player_2_wins,\
+         player_1_win_probability,player_2_win_probability,expected_turns\n",
     );
-    player_1_wins.max(player_2_wins)
+    for ((x, y), outcome) in table.iter() {
+        csv += &format!(
+            "{},{},{},{},{},{},{}\n",
+            y + 1,
+            x + 1,
+            outcome.wins[0],
+            outcome.wins[1],
+            outcome.win_probabilities[0],
+            outcome.win_probabilities[1],
+            outcome.expected_turns
+        );
+    }
+    csv
+}
+
+/// A choice the players get in a variant of the Dirac Dice game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    /// Once per game a player can ignore the rolls of a turn and roll again
+    Reroll,
+    /// The players roll twice per turn and pick which of the two sums to move by
+    PickBetweenTwo,
+}
+
+/// A player of a two player game with choices
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Seat {
+    pub position: u16,
+    pub score: u16,
+    /// Whether the player can still reroll, always false for [`Variant::PickBetweenTwo`]
+    pub can_reroll: bool,
+}
+
+impl Seat {
+    fn advance(&self, amount: u16, board_size: u16) -> Self {
+        let player = Player {
+            position: self.position,
+            score: self.score,
+        }
+        .advance(amount, board_size);
+        Self {
+            position: player.position,
+            score: player.score,
+            ..*self
+        }
+    }
+}
+
+/// The sums rolled by the player about to move
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Roll {
+    Single(u16),
+    /// The sums of the two rolls of [`Variant::PickBetweenTwo`], the smallest first
+    Pair(u16, u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Moves by this sum
+    Move(u16),
+    Reroll,
+}
+
+/// A situation where the player about to move has a choice
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Decision {
+    pub player: Seat,
+    pub opponent: Seat,
+    pub roll: Roll,
+}
+
+/// Solves two player variants of Dirac Dice where the players make choices with expectimax,
+/// both players maximize their own chance of winning which is the same as minimax here
+/// since exactly one of them wins
+pub struct OptimalPlay {
+    rules: Rules,
+    variant: Variant,
+    rolls: Vec<(u16, f64)>,
+    /// The probability that the first player, the one about to move, wins
+    cache: HashMap<(Seat, Seat), f64>,
+    policy: HashMap<Decision, Action>,
+}
+
+impl OptimalPlay {
+    pub fn new(rules: Rules, variant: Variant) -> Self {
+        assert!(rules.board_size > 0, "the board needs at least one space");
+        assert!(rules.die_faces > 0, "the die needs at least one face");
+        let distribution = rules.roll_distribution();
+        let universes = distribution.iter().map(|(_, count)| count).sum::<usize>() as f64;
+        Self {
+            rules,
+            variant,
+            rolls: distribution
+                .into_iter()
+                .map(|(sum, count)| (sum, count as f64 / universes))
+                .collect(),
+            cache: HashMap::new(),
+            policy: HashMap::new(),
+        }
+    }
+
+    /// The best achievable win probability of each player when both play optimally.
+    /// Panics if a starting position isn't on the board
+    pub fn win_probabilities(&mut self, (player_1, player_2): (u16, u16)) -> (f64, f64) {
+        let board = 1..=self.rules.board_size;
+        assert!(
+            board.contains(&player_1) && board.contains(&player_2),
+            "the starting positions must be on the board"
+        );
+        let seat = |position| Seat {
+            position,
+            score: 0,
+            can_reroll: self.variant == Variant::Reroll,
+        };
+        let probability = self.win_probability(seat(player_1), seat(player_2));
+        (probability, 1.0 - probability)
+    }
+
+    /// The best action for every decision met while solving the games so far
+    pub fn policy(&self) -> &HashMap<Decision, Action> {
+        &self.policy
+    }
+
+    fn win_probability(&mut self, player: Seat, opponent: Seat) -> f64 {
+        if let Some(value) = self.cache.get(&(player, opponent)) {
+            return *value;
+        }
+
+        let mut probability = 0.0;
+        match self.variant {
+            Variant::Reroll => {
+                let reroll = player.can_reroll.then(|| {
+                    let player = Seat {
+                        can_reroll: false,
+                        ..player
+                    };
+                    self.expected_move(player, opponent)
+                });
+                for i in 0..self.rolls.len() {
+                    let (sum, p) = self.rolls[i];
+                    let keep = self.after_move(player, opponent, sum);
+                    let best = match reroll {
+                        Some(reroll) => {
+                            let (action, best) = match reroll > keep {
+                                true => (Action::Reroll, reroll),
+                                false => (Action::Move(sum), keep),
+                            };
+                            self.decide(player, opponent, Roll::Single(sum), action);
+                            best
+                        }
+                        None => keep,
+                    };
+                    probability += p * best;
+                }
+            }
+            Variant::PickBetweenTwo => {
+                for i in 0..self.rolls.len() {
+                    for j in i..self.rolls.len() {
+                        let ((a, p_a), (b, p_b)) = (self.rolls[i], self.rolls[j]);
+                        let move_a = self.after_move(player, opponent, a);
+                        let move_b = self.after_move(player, opponent, b);
+                        let (action, best) = match move_b > move_a {
+                            true => (Action::Move(b), move_b),
+                            false => (Action::Move(a), move_a),
+                        };
+                        if a != b {
+                            self.decide(player, opponent, Roll::Pair(a, b), action);
+                        }
+                        // a pair of different sums can be rolled in either order
+                        let orders = if a == b { 1.0 } else { 2.0 };
+                        probability += orders * p_a * p_b * best;
+                    }
+                }
+            }
+        }
+
+        self.cache.insert((player, opponent), probability);
+        probability
+    }
+
+    /// The win probability of the player after moving by every possible sum
+    fn expected_move(&mut self, player: Seat, opponent: Seat) -> f64 {
+        (0..self.rolls.len())
+            .map(|i| {
+                let (sum, p) = self.rolls[i];
+                p * self.after_move(player, opponent, sum)
+            })
+            .sum()
+    }
+
+    /// The win probability of the player after moving by `sum`
+    fn after_move(&mut self, player: Seat, opponent: Seat, sum: u16) -> f64 {
+        let player = player.advance(sum, self.rules.board_size);
+        if player.score >= self.rules.target {
+            1.0
+        } else {
+            1.0 - self.win_probability(opponent, player)
+        }
+    }
+
+    fn decide(&mut self, player: Seat, opponent: Seat, roll: Roll, action: Action) {
+        let decision = Decision {
+            player,
+            opponent,
+            roll,
+        };
+        self.policy.insert(decision, action);
+    }
+}
+
+pub fn parse(input: &str) -> Result<Data, ParseError> {
+    let players = input
+        .lines()
+        .map(|l| {
+            let (player, position) =
+                scan!("Player {} starting position: {}" <- l).map_err(|_| {
+                    ParseError::new(
+                        DAY,
+                        input,
+                        l,
+                        "expected `Player <n> starting position: <n>`",
+                    )
+                })?;
+            // Both games are played on the puzzle's board
+            if !(1..=BOARD_SIZE).contains(&position) {
+                let reason = format!("the starting position must be between 1 and {}", BOARD_SIZE);
+                return Err(ParseError::new(DAY, input, l, reason));
+            }
+            Ok((player, position))
+        })
+        .collect::<Result<Vec<(u16, u16)>, _>>()?;
+
+    match players[..] {
+        [(_, player_1), (_, player_2)] => Ok((player_1, player_2)),
+        _ => Err(ParseError::new(DAY, input, input, "expected 2 players")),
+    }
+}
+
+/// Plays the practice game, the die doesn't stop turning so the game always ends
+pub fn practice_game(input: &Data) -> Game<impl Iterator<Item = u16>> {
+    let (player_1_pos, player_2_pos) = input;
+    let rules = Rules::PRACTICE;
+    Game::new(
+        rules,
+        &[*player_1_pos, *player_2_pos],
+        deterministic_die(rules.die_faces),
+    )
+}
+
+pub fn part_1(input: &Data) -> usize {
+    let mut game = practice_game(input);
+    game.by_ref().for_each(drop);
+    let losing_score = game.scores().into_iter().min().unwrap_or_default();
+    losing_score as usize * game.roll_count()
+}
+
+pub fn part_2(input: &Data) -> BigUint {
+    let (player_1_pos, player_2_pos) = input;
+    let mut dirac_dice = DiracDice::new(Rules::PUZZLE);
+    let wins = dirac_dice.wins(&[*player_1_pos, *player_2_pos]);
+    wins.into_iter().max().unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -119,17 +629,208 @@ mod tests {
         Player 2 starting position: 8
     "};
 
+    #[test]
+    pub fn parse_error() {
+        let err = super::parse("Player 1 starting position: 4\nPlayer 2 starting position: 11")
+            .unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.reason, "the starting position must be between 1 and 10");
+        assert!(
+            super::parse("Player 1 starting position: 0\nPlayer 2 starting position: 8").is_err()
+        );
+    }
+
     #[test]
     pub fn part_1() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_1(&input);
         assert_eq!(result, 739785);
     }
 
+    #[test]
+    pub fn practice_game() {
+        use super::{practice_game, Game, Rules, SeededDie, Turn};
+
+        let input = super::parse(INPUTS).unwrap();
+        let mut game = practice_game(&input);
+        let turns = game
+            .by_ref()
+            .map(|turn| turn.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            turns[..4],
+            [
+                "Player 1 rolls 1+2+3 and moves to space 10 for a total score of 10.",
+                "Player 2 rolls 4+5+6 and moves to space 3 for a total score of 3.",
+                "Player 1 rolls 7+8+9 and moves to space 4 for a total score of 14.",
+                "Player 2 rolls 10+11+12 and moves to space 6 for a total score of 9.",
+            ]
+        );
+        assert_eq!(
+            turns.last().unwrap(),
+            "Player 1 rolls 91+92+93 and moves to space 10 for a total score of 1000."
+        );
+        assert_eq!(game.winner(), Some(0));
+        assert_eq!((game.scores()[1], game.roll_count()), (745, 993));
+
+        // the die runs out before the second turn is over
+        let mut game = Game::new(Rules::PRACTICE, &[4, 8], vec![6, 6, 6, 1, 2]);
+        let turn = Turn {
+            player: 0,
+            rolls: vec![6, 6, 6],
+            position: 2,
+            score: 2,
+        };
+        assert_eq!(game.next(), Some(turn));
+        assert_eq!(game.next(), None);
+        assert_eq!((game.winner(), game.roll_count()), (None, 5));
+
+        let rolls = SeededDie::new(6, 21).take(100).collect::<Vec<_>>();
+        assert!(rolls.iter().all(|roll| (1..=6).contains(roll)));
+        assert_eq!(rolls, SeededDie::new(6, 21).take(100).collect::<Vec<_>>());
+        let game = Game::new(Rules::PRACTICE, &[4, 8], SeededDie::new(6, 21));
+        assert!(game.last().unwrap().score >= 1000);
+    }
+
+    #[test]
+    pub fn optimal_play() {
+        use super::{Action, DiracDice, OptimalPlay, Roll, Rules, Variant};
+
+        let mut reroll = OptimalPlay::new(Rules::PUZZLE, Variant::Reroll);
+        let (player_1, player_2) = reroll.win_probabilities((4, 8));
+        assert!((player_1 + player_2 - 1.0).abs() < 1e-9);
+        // up from 0.776 without choices
+        assert!((player_1 - 0.8166525890192456).abs() < 1e-9);
+        for (decision, action) in reroll.policy() {
+            let sum = match decision.roll {
+                Roll::Single(sum) => sum,
+                Roll::Pair(..) => unreachable!("only the other variant rolls pairs"),
+            };
+            let position = (decision.player.position + sum - 1) % 10 + 1;
+            if decision.player.score + position >= 21 {
+                assert_eq!(*action, Action::Move(sum), "{:?}", decision);
+            }
+        }
+
+        let mut pick = OptimalPlay::new(Rules::PUZZLE, Variant::PickBetweenTwo);
+        let (player_1, player_2) = pick.win_probabilities((4, 8));
+        assert!((player_1 + player_2 - 1.0).abs() < 1e-9);
+        assert!((player_1 - 0.8608667918479216).abs() < 1e-9);
+
+        // with a single face there is nothing to choose
+        let rules = Rules {
+            die_faces: 1,
+            ..Rules::PUZZLE
+        };
+        let expected = DiracDice::new(rules).outcome(&[4, 8]).win_probabilities;
+        for variant in [Variant::Reroll, Variant::PickBetweenTwo] {
+            let mut optimal_play = OptimalPlay::new(rules, variant);
+            let (player_1, _) = optimal_play.win_probabilities((4, 8));
+            assert!((player_1 - expected[0]).abs() < 1e-9);
+        }
+        let mut pick = OptimalPlay::new(rules, Variant::PickBetweenTwo);
+        pick.win_probabilities((4, 8));
+        assert!(pick.policy().is_empty());
+    }
+
     #[test]
     pub fn part_2() {
-        let input = super::parse(INPUTS);
+        let input = super::parse(INPUTS).unwrap();
         let result = super::part_2(&input);
-        assert_eq!(result, 444356092776315);
+        assert_eq!(result, 444356092776315u64.into());
+    }
+
+    /// The number of universes won by each player, for games small enough to fit
+    fn wins(dirac_dice: &mut super::DiracDice, positions: &[u16]) -> Vec<u128> {
+        let wins = dirac_dice.wins(positions);
+        wins.iter().map(|wins| wins.try_into().unwrap()).collect()
+    }
+
+    #[test]
+    pub fn dirac_dice() {
+        use super::{DiracDice, Rules};
+
+        assert_eq!(
+            Rules::PUZZLE.roll_distribution(),
+            [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)]
+        );
+
+        let mut dirac_dice = DiracDice::new(Rules::PUZZLE);
+        assert_eq!(
+            wins(&mut dirac_dice, &[4, 8]),
+            [444356092776315, 341960390180808]
+        );
+
+        let outcome = dirac_dice.outcome(&[4, 8]);
+        let total = outcome.win_probabilities.iter().sum::<f64>();
+        assert!((total - 1.0).abs() < 1e-9);
+        // the first player wins 57% of the universes but 78% of the games
+        assert!((outcome.win_probabilities[0] - 0.776043913674338).abs() < 1e-9);
+        assert!((outcome.expected_turns - 6.918711699298746).abs() < 1e-9);
+
+        let table = dirac_dice.outcome_table();
+        assert_eq!((table.width(), table.height()), (10, 10));
+        assert_eq!(table[(7, 3)], outcome);
+
+        // the first player wins in every universe before anyone else plays
+        let rules = Rules {
+            board_size: 5,
+            die_faces: 6,
+            rolls_per_turn: 2,
+            target: 1,
+        };
+        assert_eq!(wins(&mut DiracDice::new(rules), &[1, 2, 3]), [36, 0, 0]);
+
+        // with a single space every player scores 1 per turn so the first to play wins
+        let rules = Rules {
+            board_size: 1,
+            die_faces: 2,
+            rolls_per_turn: 1,
+            target: 3,
+        };
+        let wins = wins(&mut DiracDice::new(rules), &[1, 1, 1]);
+        assert_eq!(wins, [2 * 2 * 2 * 2 * 2 * 2 * 2, 0, 0]);
+    }
+
+    #[test]
+    pub fn three_players() {
+        use super::{DiracDice, Rules};
+
+        // more universes than a u64 can count
+        let mut dirac_dice = DiracDice::new(Rules::PUZZLE);
+        assert_eq!(
+            wins(&mut dirac_dice, &[4, 8, 1]),
+            [
+                278122794029600828547,
+                253477283751906861031,
+                302796583677595829173
+            ]
+        );
+        let outcome = dirac_dice.outcome(&[4, 8, 1]);
+        let total = outcome.win_probabilities.iter().sum::<f64>();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    pub fn outcome_table_csv() {
+        use super::{outcome_table_csv, DiracDice, Rules};
+
+        // with a single space every player scores 1 per turn so the first to play wins
+        let rules = Rules {
+            board_size: 1,
+            die_faces: 2,
+            rolls_per_turn: 1,
+            target: 3,
+        };
+        let mut dirac_dice = DiracDice::new(rules);
+        assert_eq!(dirac_dice.outcome(&[1, 1]).expected_turns, 5.0);
+        let csv = outcome_table_csv(&dirac_dice.outcome_table());
+        assert_eq!(
+            csv,
+            indoc! {"
+                player_1,player_2,player_1_wins,player_2_wins,player_1_win_probability,player_2_win_probability,expected_turns
+                1,1,32,0,1,0,5
+            "}
+        );
     }
 }
@@ -0,0 +1,119 @@
+use std::{fmt, str::FromStr};
+
+/// Error returned when a puzzle input is malformed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    /// 1-based, 0 if the offending text isn't part of the input
+    pub line: usize,
+    /// 1-based and counted in chars, 0 if the offending text isn't part of the input
+    pub column: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    /// Creates an error for `text`, the line and column are found from the position of `text` in `input`
+    pub fn new(day: u8, input: &str, text: &str, reason: impl Into<String>) -> Self {
+        let (line, column) = locate(input, text);
+        Self {
+            day,
+            line,
+            column,
+            text: text.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Used when the error was created from a single line that is part of a larger input
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} line {} column {}: {} `{}`",
+            self.day, self.line, self.column, self.reason, self.text
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Finds the 1-based line and column of `text`, it needs to be a slice of `input`
+fn locate(input: &str, text: &str) -> (usize, usize) {
+    let offset = (text.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+    if offset > input.len() || !input.is_char_boundary(offset) {
+        return (0, 0);
+    }
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+pub fn parse_number<T>(day: u8, input: &str, text: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text.parse()
+        .map_err(|err| ParseError::new(day, input, text, format!("invalid number ({})", err)))
+}
+
+/// Parses every char of the line as a digit of the given radix
+pub fn parse_digits(day: u8, input: &str, line: &str, radix: u32) -> Result<Vec<u32>, ParseError> {
+    line.char_indices()
+        .map(|(i, c)| {
+            c.to_digit(radix).ok_or_else(|| {
+                ParseError::new(day, input, &line[i..i + c.len_utf8()], "expected a digit")
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::{parse_digits, parse_number, ParseError};
+
+    const INPUTS: &str = indoc! {"
+        123
+        45x6
+    "};
+
+    #[test]
+    pub fn locate() {
+        let line = INPUTS.lines().nth(1).unwrap();
+        let err = ParseError::new(1, INPUTS, &line[2..3], "expected a digit");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.text, "x");
+        assert_eq!(
+            err.to_string(),
+            "day 1 line 2 column 3: expected a digit `x`"
+        );
+
+        let err = ParseError::new(1, INPUTS, "not in the input", "oops");
+        assert_eq!((err.line, err.column), (0, 0));
+    }
+
+    #[test]
+    pub fn parse_helpers() {
+        let line = INPUTS.lines().nth(1).unwrap();
+        let err = parse_number::<u32>(1, INPUTS, line).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+
+        let err = parse_digits(1, INPUTS, line, 10).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let line = INPUTS.lines().next().unwrap();
+        assert_eq!(parse_digits(1, INPUTS, line, 10), Ok(vec![1, 2, 3]));
+        assert_eq!(parse_number(1, INPUTS, line), Ok(123));
+    }
+}
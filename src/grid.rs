@@ -0,0 +1,295 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::{self, ParseError};
+
+pub type Pos = (isize, isize);
+
+pub const NEIGHBOURS_4: [Pos; 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+pub const NEIGHBOURS_8: [Pos; 8] = [
+    (0, 1),
+    (0, -1),
+    (1, 0),
+    (1, 1),
+    (1, -1),
+    (-1, 0),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// A 2d grid stored as a single row major `Vec`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            data: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, returns `None` if the rows don't all have the same width
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        let height = rows.len();
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Pos) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        self.contains(pos)
+            .then(|| pos.0 as usize + pos.1 as usize * self.width)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.data[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.data[i])
+    }
+
+    fn neighbours<'a>(&'a self, pos: Pos, offsets: &'a [Pos]) -> impl Iterator<Item = Pos> + 'a {
+        offsets
+            .iter()
+            .map(move |(x, y)| (pos.0 + x, pos.1 + y))
+            .filter(move |n| self.contains(*n))
+    }
+
+    /// The positions of the horizontal and vertical neighbours inside the grid
+    pub fn neighbours_4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &NEIGHBOURS_4)
+    }
+
+    /// The positions of the neighbours inside the grid, including the diagonals
+    pub fn neighbours_8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, &NEIGHBOURS_8)
+    }
+
+    /// Every position in row major order
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x as isize, y as isize)))
+    }
+
+    /// Every position with its value in row major order
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.data.iter())
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics with a size of 0
+        self.data.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} out of bounds", x);
+        self.data.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
+    /// Parses every character of the lines in `text` with `f`, `input` is only used to locate errors
+    pub fn parse_chars(
+        day: u8,
+        input: &str,
+        text: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let rows = text
+            .lines()
+            .map(|line| {
+                line.char_indices()
+                    .map(|(i, c)| {
+                        f(c).ok_or_else(|| {
+                            let text = &line[i..i + c.len_utf8()];
+                            ParseError::new(day, input, text, "unexpected character")
+                        })
+                    })
+                    .collect()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_grid_rows(day, input, text, rows)
+    }
+
+    fn from_grid_rows(
+        day: u8,
+        input: &str,
+        text: &str,
+        rows: Vec<Vec<T>>,
+    ) -> Result<Self, ParseError> {
+        let width = rows.first().map_or(0, |row| row.len());
+        match text.lines().zip(&rows).find(|(_, row)| row.len() != width) {
+            Some((line, _)) => Err(ParseError::new(
+                day,
+                input,
+                line,
+                "expected rows of the same width",
+            )),
+            None => Ok(Self::from_rows(rows).expect("rows have the same width")),
+        }
+    }
+}
+
+impl Grid<u32> {
+    /// Parses a grid of single digits, `input` is only used to locate errors
+    pub fn parse_digits(day: u8, input: &str, text: &str) -> Result<Self, ParseError> {
+        let rows = text
+            .lines()
+            .map(|line| error::parse_digits(day, input, line, 10))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_grid_rows(day, input, text, rows)
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &self.data[i],
+            None => panic!("{:?} is outside of the grid", pos),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        match self.index_of(pos) {
+            Some(i) => &mut self.data[i],
+            None => panic!("{:?} is outside of the grid", pos),
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::Grid;
+
+    const INPUTS: &str = indoc! {"
+        123
+        456
+    "};
+
+    #[test]
+    pub fn access() {
+        let mut grid = Grid::parse_digits(0, INPUTS, INPUTS).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((2, 1)), Some(&6));
+        assert_eq!(grid.get((-1, 0)), None);
+        assert_eq!(grid.get((3, 0)), None);
+        *grid.get_mut((0, 1)).unwrap() = 9;
+        grid[(1, 1)] += 1;
+        assert_eq!(grid.row(1), [9, 6, 6]);
+        assert_eq!(grid.to_string(), "123\n966\n");
+    }
+
+    #[test]
+    pub fn neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        let mut corner = grid.neighbours_4((0, 0)).collect::<Vec<_>>();
+        corner.sort_unstable();
+        assert_eq!(corner, [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours_8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    pub fn rows_and_columns() {
+        let grid = Grid::parse_digits(0, INPUTS, INPUTS).unwrap();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        let columns = grid
+            .columns()
+            .map(|column| column.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(columns, [[1, 4], [2, 5], [3, 6]]);
+        assert_eq!(
+            grid.iter().nth(4).map(|(pos, v)| (pos, *v)),
+            Some(((1, 1), 5))
+        );
+    }
+
+    #[test]
+    pub fn parse_error() {
+        let input = "#.#\n#x#";
+        let err = Grid::parse_chars(0, input, input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+
+        let input = "123\n45";
+        let err = Grid::parse_digits(0, input, input).unwrap_err();
+        assert_eq!(err.reason, "expected rows of the same width");
+        assert_eq!(err.line, 2);
+    }
+}
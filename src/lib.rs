@@ -0,0 +1,101 @@
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+
+pub mod bench;
+pub mod grid;
+pub mod pathfinding;
+pub mod runner;
+
+mod error;
+mod solution;
+
+pub use error::ParseError;
+pub use solution::{IntoAnswer, ParsedInput, Registry, Solution, Solver};
+
+/// Implements [`Solution`] for each day by forwarding to the day's parse, part_1 and part_2
+/// and builds the registry containing all of them.
+/// The output of part 2 only needs to be given when it differs from part 1
+macro_rules! solutions {
+    (@output_2 $output:ty) => { $output };
+    (@output_2 $output:ty, $output_2:ty) => { $output_2 };
+    (
+        year: $year:literal;
+        $($day:ident($day_number:literal): $name:ident, $input:ident => $output:ty $(, $output_2:ty)?;)*
+    ) => {
+        $(
+            pub struct $name;
+
+            impl Solution for $name {
+                const YEAR: u16 = $year;
+                const DAY: u8 = $day_number;
+
+                type Input = $day::$input;
+                type Output1 = $output;
+                type Output2 = solutions!(@output_2 $output $(, $output_2)?);
+
+                fn parse(input: &str) -> Result<Self::Input, ParseError> {
+                    $day::parse(input)
+                }
+
+                fn part_1(input: &Self::Input) -> Self::Output1 {
+                    $day::part_1(input)
+                }
+
+                fn part_2(input: &Self::Input) -> Self::Output2 {
+                    $day::part_2(input)
+                }
+            }
+        )*
+
+        /// Creates a registry with every solved day
+        pub fn registry() -> Registry {
+            let mut registry = Registry::new();
+            $(registry.register($name);)*
+            registry
+        }
+    };
+}
+
+solutions! {
+    year: 2021;
+    day01(1): Day01, Data => usize;
+    day02(2): Day02, Data => i32;
+    day03(3): Day03, Data => usize;
+    day04(4): Day04, Data => usize;
+    day05(5): Day05, Data => usize;
+    day06(6): Day06, Data => usize;
+    day07(7): Day07, Data => usize;
+    day08(8): Day08, Data => usize;
+    day09(9): Day09, Data => u32;
+    day10(10): Day10, Data => usize;
+    day11(11): Day11, Data => usize;
+    day12(12): Day12, Graph => usize;
+    day13(13): Day13, Data => usize;
+    day14(14): Day14, Data => usize;
+    day15(15): Day15, Data => i32;
+    day16(16): Day16, Packet => usize, Result<u128, day16::EvalError>;
+    day17(17): Day17, TargetArea => usize;
+    day18(18): Day18, Data => u16;
+    day19(19): Day19, Data => usize;
+    day20(20): Day20, Data => usize;
+    day21(21): Day21, Data => usize, num_bigint::BigUint;
+}
@@ -1,24 +0,0 @@
-aoc_helper::main! {
-    year: 2021;
-    day01: parse => part_1, part_2;
-    day02: parse => part_1, part_2;
-    day03: parse => part_1, part_2;
-    day04: parse => part_1, part_2;
-    day05: parse => part_1, part_2;
-    day06: parse => part_1, part_2;
-    day07: parse => part_1, part_2;
-    day08: parse => part_1, part_2;
-    day09: parse => part_1, part_2;
-    day10: parse => part_1, part_2;
-    day11: parse => part_1, part_2;
-    day12: parse => part_1, part_2;
-    day13: parse => part_1, part_2;
-    day14: parse => part_1, part_2;
-    day15: parse => part_1, part_2;
-    day16: parse => part_1, part_2;
-    day17: parse => part_1, part_2;
-    day18: parse => part_1, part_2;
-    day19: parse => part_1, part_2;
-    day20: parse => part_1, part_2;
-    day21: parse => part_1, part_2;
-}
@@ -0,0 +1,149 @@
+use std::{collections::VecDeque, hash::Hash};
+
+use hashbrown::HashSet;
+
+use crate::grid::{Pos, NEIGHBOURS_4};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    /// Every node of the path, including the start and the goal
+    pub nodes: Vec<N>,
+}
+
+/// Finds the cheapest path from `start` to `goal` on a `width` x `height` grid moving in the
+/// 4 directions. `cost` gives the cost of entering a position, `None` if it can't be entered.
+///
+/// The costs are stored in a flat array indexed by `y * width + x` and since a step costs at most
+/// `max_cost` the queue is a ring of `max_cost + 1` buckets, one per cost modulo its length.
+/// That only takes 5 bytes per position. Returns `None` if a cost is above `max_cost`
+pub fn grid_dijkstra(
+    (width, height): (usize, usize),
+    start: Pos,
+    goal: Pos,
+    max_cost: u32,
+    mut cost: impl FnMut(Pos) -> Option<u32>,
+) -> Option<Path<Pos, u32>> {
+    let inside = |(x, y): Pos| x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height;
+    if !inside(start) || !inside(goal) {
+        return None;
+    }
+    let index = |(x, y): Pos| y as usize * width + x as usize;
+    let mut costs = vec![u32::MAX; width * height];
+    // The index in NEIGHBOURS_4 of the step that reached each position
+    let mut steps = vec![0u8; width * height];
+    let ring = max_cost as usize + 1;
+    let mut buckets = vec![vec![]; ring];
+    costs[index(start)] = 0;
+    buckets[0].push(start);
+
+    let mut queued = 1;
+    let mut current = 0u32;
+    while queued > 0 {
+        let bucket = current as usize % ring;
+        while let Some(pos) = buckets[bucket].pop() {
+            queued -= 1;
+            if costs[index(pos)] != current {
+                continue;
+            }
+            if pos == goal {
+                let mut nodes = vec![goal];
+                let mut pos = goal;
+                while pos != start {
+                    let (x, y) = NEIGHBOURS_4[steps[index(pos)] as usize];
+                    pos = (pos.0 - x, pos.1 - y);
+                    nodes.push(pos);
+                }
+                nodes.reverse();
+                return Some(Path {
+                    cost: current,
+                    nodes,
+                });
+            }
+            for (step, (x, y)) in NEIGHBOURS_4.iter().enumerate() {
+                let n = (pos.0 + x, pos.1 + y);
+                if !inside(n) {
+                    continue;
+                }
+                let n_cost = match cost(n) {
+                    Some(n_cost) if n_cost > max_cost => return None,
+                    Some(n_cost) => current + n_cost,
+                    None => continue,
+                };
+                if n_cost < costs[index(n)] {
+                    costs[index(n)] = n_cost;
+                    steps[index(n)] = step as u8;
+                    buckets[n_cost as usize % ring].push(n);
+                    queued += 1;
+                }
+            }
+        }
+        current += 1;
+    }
+    None
+}
+
+/// Every node that can be reached from `start`, including `start`
+pub fn reachable<N, FN, IN>(start: N, mut neighbours: FN) -> HashSet<N>
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from([start.clone()]);
+    visited.insert(start);
+    while let Some(node) = queue.pop_front() {
+        for n in neighbours(&node) {
+            if visited.insert(n.clone()) {
+                queue.push_back(n);
+            }
+        }
+    }
+    visited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{grid_dijkstra, reachable};
+
+    /// a -> b -> c -> d and a -> d
+    fn graph(node: &char) -> Vec<char> {
+        match node {
+            'a' => vec!['b', 'd'],
+            'b' => vec!['c'],
+            'c' => vec!['d'],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    pub fn grid() {
+        // 1 1 9
+        // 5 # 1
+        // 1 1 1
+        let cost = |(x, y)| match (x, y) {
+            (1, 1) => None,
+            (2, 0) => Some(9),
+            (0, 1) => Some(5),
+            _ => Some(1),
+        };
+        let path = grid_dijkstra((3, 3), (0, 0), (2, 2), 9, cost).unwrap();
+        assert_eq!(path.cost, 8);
+        assert_eq!(path.nodes, [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+        let path = grid_dijkstra((3, 3), (0, 0), (2, 0), 9, cost).unwrap();
+        assert_eq!((path.cost, path.nodes.len()), (10, 3));
+
+        assert_eq!(grid_dijkstra((3, 3), (0, 0), (1, 1), 9, cost), None);
+        assert_eq!(grid_dijkstra((3, 3), (0, 0), (3, 0), 9, cost), None);
+        // a cost above the maximum
+        assert_eq!(grid_dijkstra((3, 3), (0, 0), (2, 2), 8, cost), None);
+    }
+
+    #[test]
+    pub fn reachable_nodes() {
+        assert_eq!(reachable('a', graph).len(), 4);
+        assert_eq!(reachable('b', graph).len(), 3);
+        assert_eq!(reachable('d', graph).len(), 1);
+    }
+}
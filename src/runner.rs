@@ -0,0 +1,407 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::Read,
+    panic::{catch_unwind, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use rayon::prelude::*;
+use serde_derive::Deserialize;
+
+use crate::{ParseError, Solver};
+
+/// The inputs are stored as `inputs/<year>/<day>.txt`
+pub fn input_path(year: u16, day: u8) -> PathBuf {
+    PathBuf::from(format!("inputs/{}/{:02}.txt", year, day))
+}
+
+/// Reads the input at the given path, `-` reads from stdin
+pub fn read_input(path: &Path) -> anyhow::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("failed to read input from stdin")?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read input {}", path.display()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl std::str::FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("invalid part {}, expected 1 or 2", s)),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayAnswers {
+    pub year: u16,
+    pub day: u8,
+    pub part_1: Option<String>,
+    pub part_2: Option<String>,
+}
+
+impl DayAnswers {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part_1.as_deref(),
+            Part::Two => self.part_2.as_deref(),
+        }
+    }
+}
+
+/// Parses the input and runs the given parts
+pub fn run(solver: &dyn Solver, input: &str, parts: &[Part]) -> Result<DayAnswers, DayError> {
+    let parsed = solver.parse_input(input).map_err(DayError::Parse)?;
+    let answer = |part: Part| {
+        parts
+            .contains(&part)
+            .then(|| match part {
+                Part::One => parsed.part_1(),
+                Part::Two => parsed.part_2(),
+            })
+            .transpose()
+            .map_err(|message| DayError::Part(part, message))
+    };
+    Ok(DayAnswers {
+        year: solver.year(),
+        day: solver.day(),
+        part_1: answer(Part::One)?,
+        part_2: answer(Part::Two)?,
+    })
+}
+
+#[derive(Debug)]
+pub enum DayError {
+    Input(anyhow::Error),
+    Parse(ParseError),
+    /// The part couldn't be solved for this input
+    Part(Part, String),
+    Panic(String),
+}
+
+impl fmt::Display for DayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DayError::Input(err) => write!(f, "{:#}", err),
+            DayError::Parse(err) => write!(f, "{}", err),
+            DayError::Part(part, message) => write!(f, "part {} failed: {}", part, message),
+            DayError::Panic(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DayError {}
+
+#[derive(Debug)]
+pub struct DayReport {
+    pub year: u16,
+    pub day: u8,
+    /// Time spent on the day, including reading the input
+    pub elapsed: Duration,
+    pub result: Result<DayAnswers, DayError>,
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Runs every solver concurrently, a day that fails or panics doesn't stop the other days
+///
+/// The reports are in the same order as the solvers
+pub fn run_parallel<F>(solvers: &[&dyn Solver], read_input: F, parts: &[Part]) -> Vec<DayReport>
+where
+    F: Fn(&dyn Solver) -> anyhow::Result<String> + Sync,
+{
+    solvers
+        .par_iter()
+        .map(|solver| {
+            let start = Instant::now();
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let input = read_input(*solver).map_err(DayError::Input)?;
+                run(*solver, &input, parts)
+            }))
+            .unwrap_or_else(|payload| Err(DayError::Panic(panic_message(payload))));
+            DayReport {
+                year: solver.year(),
+                day: solver.day(),
+                elapsed: start.elapsed(),
+                result,
+            }
+        })
+        .collect()
+}
+
+/// An answer can be written as a number or a string in the answers file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ExpectedAnswer {
+    pub part_1: Option<Answer>,
+    pub part_2: Option<Answer>,
+}
+
+/// Recorded answers, stored as a toml file with a `[dayNN]` table per day
+///
+/// ```toml
+/// [day01]
+/// part_1 = 7
+/// part_2 = 5
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct ExpectedAnswers {
+    days: BTreeMap<String, ExpectedAnswer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: Part,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} part {}: expected {} but got {}",
+            self.day, self.part, self.expected, self.actual
+        )
+    }
+}
+
+impl ExpectedAnswers {
+    pub fn from_toml(str: &str) -> anyhow::Result<Self> {
+        toml::from_str(str).context("failed to parse expected answers")
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let str = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read answers {}", path.display()))?;
+        Self::from_toml(&str)
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&Answer> {
+        let expected = self.days.get(&format!("day{:02}", day))?;
+        match part {
+            Part::One => expected.part_1.as_ref(),
+            Part::Two => expected.part_2.as_ref(),
+        }
+    }
+
+    /// Compares the answers to the recorded ones, parts without a recorded answer are ignored
+    pub fn check(&self, answers: &DayAnswers) -> Vec<Mismatch> {
+        Part::ALL
+            .iter()
+            .filter_map(|part| {
+                let expected = self.get(answers.day, *part)?.to_string();
+                let actual = answers.get(*part)?;
+                (expected != actual).then(|| Mismatch {
+                    day: answers.day,
+                    part: *part,
+                    expected,
+                    actual: actual.to_string(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::{run, run_parallel, DayError, ExpectedAnswers, Part};
+    use crate::{Day01, Day02, ParseError, Solution, Solver};
+
+    struct Panics;
+
+    /// Part 2 fails on an empty input
+    struct Fails;
+
+    impl Solution for Fails {
+        const YEAR: u16 = 2021;
+        const DAY: u8 = 24;
+        type Input = String;
+        type Output1 = usize;
+        type Output2 = Result<usize, String>;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            Ok(input.to_string())
+        }
+
+        fn part_1(input: &Self::Input) -> Self::Output1 {
+            input.len()
+        }
+
+        fn part_2(input: &Self::Input) -> Self::Output2 {
+            match input.len() {
+                0 => Err("empty input".to_string()),
+                len => Ok(len),
+            }
+        }
+    }
+
+    impl Solution for Panics {
+        const YEAR: u16 = 2021;
+        const DAY: u8 = 25;
+        type Input = ();
+        type Output1 = usize;
+        type Output2 = usize;
+
+        fn parse(_: &str) -> Result<Self::Input, ParseError> {
+            Ok(())
+        }
+
+        fn part_1(_: &Self::Input) -> Self::Output1 {
+            panic!("oops")
+        }
+
+        fn part_2(_: &Self::Input) -> Self::Output2 {
+            0
+        }
+    }
+
+    const INPUTS: &str = indoc! {"
+        199
+        200
+        208
+        210
+        200
+        207
+        240
+        269
+        260
+        263
+    "};
+
+    #[test]
+    pub fn run_parts() {
+        let answers = run(&Day01, INPUTS, &Part::ALL).unwrap();
+        assert_eq!(answers.part_1.as_deref(), Some("7"));
+        assert_eq!(answers.part_2.as_deref(), Some("5"));
+
+        let answers = run(&Day01, INPUTS, &[Part::Two]).unwrap();
+        assert_eq!(answers.part_1, None);
+        assert_eq!(answers.part_2.as_deref(), Some("5"));
+
+        assert!(matches!(
+            run(&Day01, "12\nab", &Part::ALL),
+            Err(DayError::Parse(_))
+        ));
+
+        let answers = run(&Fails, "abc", &Part::ALL).unwrap();
+        assert_eq!(answers.part_2.as_deref(), Some("3"));
+        let err = run(&Fails, "", &Part::ALL).unwrap_err();
+        assert!(matches!(err, DayError::Part(Part::Two, _)));
+        assert_eq!(err.to_string(), "part 2 failed: empty input");
+        // the failing part isn't run
+        assert!(run(&Fails, "", &[Part::One]).is_ok());
+    }
+
+    #[test]
+    pub fn parallel() {
+        let solvers: [&dyn Solver; 4] = [&Day01, &Day02, &Fails, &Panics];
+        let reports = run_parallel(
+            &solvers,
+            |solver| match solver.day() {
+                1 => Ok(INPUTS.to_string()),
+                24 => Ok(String::new()),
+                _ => anyhow::bail!("missing input"),
+            },
+            &Part::ALL,
+        );
+        assert_eq!(
+            reports.iter().map(|report| report.day).collect::<Vec<_>>(),
+            [1, 2, 24, 25]
+        );
+        assert_eq!(
+            reports[0].result.as_ref().unwrap().part_1.as_deref(),
+            Some("7")
+        );
+        assert!(matches!(reports[1].result, Err(DayError::Input(_))));
+
+        assert!(matches!(
+            reports[2].result,
+            Err(DayError::Part(Part::Two, _))
+        ));
+
+        let reports = run_parallel(&solvers[3..], |_| Ok(String::new()), &Part::ALL);
+        match &reports[0].result {
+            Err(DayError::Panic(message)) => assert_eq!(message, "oops"),
+            result => panic!("expected a panic, got {:?}", result),
+        }
+    }
+
+    #[test]
+    pub fn check() {
+        let expected = ExpectedAnswers::from_toml(indoc! {r#"
+            [day01]
+            part_1 = 7
+            part_2 = "6"
+        "#})
+        .unwrap();
+        let answers = run(&Day01, INPUTS, &Part::ALL).unwrap();
+        let mismatches = expected.check(&answers);
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].part, Part::Two);
+        assert_eq!(
+            mismatches[0].to_string(),
+            "day 1 part 2: expected 6 but got 5"
+        );
+
+        assert!(ExpectedAnswers::default().check(&answers).is_empty());
+    }
+}
@@ -0,0 +1,172 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use num_bigint::BigUint;
+
+use crate::ParseError;
+
+/// A puzzle solution split in the usual parse => part_1, part_2 steps
+pub trait Solution {
+    const YEAR: u16;
+    const DAY: u8;
+
+    type Input;
+    /// The answers of each part
+    type Output1: IntoAnswer;
+    type Output2: IntoAnswer;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part_1(input: &Self::Input) -> Self::Output1;
+    fn part_2(input: &Self::Input) -> Self::Output2;
+}
+
+/// The output of a part, a part that can fail returns a `Result` and its error is reported
+pub trait IntoAnswer {
+    fn into_answer(self) -> Result<String, String>;
+}
+
+macro_rules! display_answers {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoAnswer for $ty {
+                fn into_answer(self) -> Result<String, String> {
+                    Ok(self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+display_answers!(u16, u32, u64, u128, usize, i32, i64, String, BigUint);
+
+impl<T: Display, E: Display> IntoAnswer for Result<T, E> {
+    fn into_answer(self) -> Result<String, String> {
+        self.map(|answer| answer.to_string())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Object safe version of [`Solution`] so solutions can be stored and driven generically
+pub trait Solver: Send + Sync {
+    fn year(&self) -> u16;
+    fn day(&self) -> u8;
+    fn parse_input(&self, input: &str) -> Result<Box<dyn ParsedInput>, ParseError>;
+}
+
+/// The parsed input of a [`Solver`], the answers are formatted since each day has its own type.
+/// A part that couldn't be solved returns why
+pub trait ParsedInput {
+    fn part_1(&self) -> Result<String, String>;
+    fn part_2(&self) -> Result<String, String>;
+}
+
+struct Parsed<S: Solution>(S::Input);
+
+impl<S: Solution> ParsedInput for Parsed<S> {
+    fn part_1(&self) -> Result<String, String> {
+        S::part_1(&self.0).into_answer()
+    }
+
+    fn part_2(&self) -> Result<String, String> {
+        S::part_2(&self.0).into_answer()
+    }
+}
+
+impl<S> Solver for S
+where
+    S: Solution + Send + Sync + 'static,
+    S::Input: 'static,
+{
+    fn year(&self) -> u16 {
+        S::YEAR
+    }
+
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn parse_input(&self, input: &str) -> Result<Box<dyn ParsedInput>, ParseError> {
+        Ok(Box::new(Parsed::<S>(S::parse(input)?)))
+    }
+}
+
+/// Maps a (year, day) to its solver
+#[derive(Default)]
+pub struct Registry {
+    solvers: BTreeMap<(u16, u8), Box<dyn Solver>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<S: Solver + 'static>(&mut self, solver: S) {
+        self.solvers
+            .insert((solver.year(), solver.day()), Box::new(solver));
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Option<&dyn Solver> {
+        self.solvers.get(&(year, day)).map(|solver| solver.as_ref())
+    }
+
+    /// Iterates over all the solvers ordered by year and day
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.solvers.values().map(|solver| solver.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.solvers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solvers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::{Registry, Solution};
+    use crate::Day01;
+
+    const INPUTS: &str = indoc! {"
+        199
+        200
+        208
+        210
+        200
+        207
+        240
+        269
+        260
+        263
+    "};
+
+    #[test]
+    pub fn solution() {
+        let input = Day01::parse(INPUTS).unwrap();
+        assert_eq!(Day01::part_1(&input), 7);
+        assert_eq!(Day01::part_2(&input), 5);
+    }
+
+    #[test]
+    pub fn registry() {
+        let mut registry = Registry::new();
+        registry.register(Day01);
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get(2021, 2).is_none());
+
+        let input = registry.get(2021, 1).unwrap().parse_input(INPUTS).unwrap();
+        assert_eq!(input.part_1().as_deref(), Ok("7"));
+        assert_eq!(input.part_2().as_deref(), Ok("5"));
+
+        let registry = crate::registry();
+        assert_eq!(registry.len(), 21);
+        let days = registry
+            .iter()
+            .map(|solver| solver.day())
+            .collect::<Vec<_>>();
+        assert_eq!(days, (1..=21).collect::<Vec<_>>());
+    }
+}